@@ -33,6 +33,20 @@ pub fn with_header(content: Element<AppMessage>) -> Element<AppMessage> {
     .on_press(AppMessage::PlotterButtonPressed)
     .style(styles::rounded_button_style);
 
+    let processes_page = button(
+        container(
+            svg(svg::Handle::from_path("assets/icons/list.svg"))
+                .width(30)
+                .height(30),
+        )
+        .align_x(Center)
+        .align_y(Center)
+        .width(35)
+        .height(35),
+    )
+    .on_press(AppMessage::ProcessesButtonPressed)
+    .style(styles::rounded_button_style);
+
     let settings_page = button(
         container(
             svg(svg::Handle::from_path("assets/icons/settings.svg"))
@@ -48,15 +62,20 @@ pub fn with_header(content: Element<AppMessage>) -> Element<AppMessage> {
     .style(styles::rounded_button_style);
 
     let header = container(
-        row![main_page_button, plotter_page, settings_page]
-            .align_y(Center)
-            .spacing(8),
+        row![
+            main_page_button,
+            plotter_page,
+            processes_page,
+            settings_page
+        ]
+        .align_y(Center)
+        .spacing(8),
     )
     .padding(10)
     .align_x(Center)
     .align_y(Center)
     .style(styles::header_container_style)
-    .width(250);
+    .width(300);
 
     // center the header horizontally at top
     let header_wrapper = container(header).width(Fill).center_x(Fill);
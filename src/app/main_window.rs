@@ -1,25 +1,52 @@
 use crate::app::styles;
+use crate::collectors::battery_collector::BatteryData;
 use crate::collectors::cpu_collector::CpuData;
+use crate::collectors::gpu_collector::GpuData;
+use crate::collectors::memory_collector::MemoryData;
+use crate::collectors::motherboard_collector::MotherboardData;
+use crate::collectors::network_collector::InterfaceData;
+use crate::collectors::process_collector::BYTES_PER_MB;
+use crate::collectors::psu_collector::{cpu_share_of_wall, total_wall_draw, PsuData};
+use crate::collectors::storage_collector::StorageData;
+use crate::collectors::topology_collector::CpuTopology;
+use crate::collectors::CoreStats;
 use iced::widget::{
-    button, column, container, progress_bar, rich_text, row, rule, span, svg, text, Row,
+    button, column, container, progress_bar, rich_text, row, rule, span, svg, text, Column, Row,
 };
 use iced::{font, never, window, Center, Element, Fill, Font, Padding, Subscription};
 use lilt::{Animated, Easing};
 use std::time::Instant;
 
+// Shown under "Unavailable" when no backend reports a CPU temperature
+#[cfg(windows)]
+const NO_TEMP_SOURCE: &str = "Hardware monitor not connected";
+#[cfg(not(windows))]
+const NO_TEMP_SOURCE: &str = "No CPU temperature sensor found";
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BarChartState {
     Usage,
     Power,
+    Temperature,
+    Frequency,
 }
 
 #[derive(Debug, Clone)]
 pub enum MainWindowMessage {
     UsageButtonPressed,
     PowerButtonPressed,
+    TemperatureButtonPressed,
+    FrequencyButtonPressed,
     // Animation triggers
     ToggleGeneralInfo,
     ToggleCoresCard,
+    ToggleMemoryCard,
+    ToggleGpuCard,
+    ToggleStorageCard,
+    ToggleMotherboardCard,
+    ToggleNetworkCard,
+    ToggleBatteryCard,
+    TogglePsuCard,
     Tick, // Frame update (REQUIRED for animations)
 }
 
@@ -27,6 +54,13 @@ pub struct MainWindow {
     bar_chart_state: BarChartState,
     general_info_expanded: Animated<f32, Instant>,
     cores_card_expanded: Animated<f32, Instant>,
+    memory_card_expanded: Animated<f32, Instant>,
+    gpu_card_expanded: Animated<f32, Instant>,
+    storage_card_expanded: Animated<f32, Instant>,
+    motherboard_card_expanded: Animated<f32, Instant>,
+    network_card_expanded: Animated<f32, Instant>,
+    battery_card_expanded: Animated<f32, Instant>,
+    psu_card_expanded: Animated<f32, Instant>,
     now: Instant,
 }
 
@@ -36,6 +70,13 @@ impl MainWindow {
             bar_chart_state: BarChartState::Usage,
             general_info_expanded: Animated::new(1.0).duration(400.0).easing(Easing::EaseInOut),
             cores_card_expanded: Animated::new(1.0).duration(400.0).easing(Easing::EaseInOut),
+            memory_card_expanded: Animated::new(1.0).duration(400.0).easing(Easing::EaseInOut),
+            gpu_card_expanded: Animated::new(1.0).duration(400.0).easing(Easing::EaseInOut),
+            storage_card_expanded: Animated::new(1.0).duration(400.0).easing(Easing::EaseInOut),
+            motherboard_card_expanded: Animated::new(1.0).duration(400.0).easing(Easing::EaseInOut),
+            network_card_expanded: Animated::new(1.0).duration(400.0).easing(Easing::EaseInOut),
+            battery_card_expanded: Animated::new(1.0).duration(400.0).easing(Easing::EaseInOut),
+            psu_card_expanded: Animated::new(1.0).duration(400.0).easing(Easing::EaseInOut),
             now: Instant::now(),
         }
     }
@@ -48,6 +89,12 @@ impl MainWindow {
             MainWindowMessage::PowerButtonPressed => {
                 self.bar_chart_state = BarChartState::Power;
             }
+            MainWindowMessage::TemperatureButtonPressed => {
+                self.bar_chart_state = BarChartState::Temperature;
+            }
+            MainWindowMessage::FrequencyButtonPressed => {
+                self.bar_chart_state = BarChartState::Frequency;
+            }
             MainWindowMessage::ToggleGeneralInfo => {
                 // 0.0 Collapsed, 1.0 Expanded
                 let new_value = if self.general_info_expanded.value > 0.5 {
@@ -68,6 +115,15 @@ impl MainWindow {
                 self.cores_card_expanded
                     .transition(new_value, Instant::now());
             }
+            MainWindowMessage::ToggleMemoryCard => toggle_card(&mut self.memory_card_expanded),
+            MainWindowMessage::ToggleGpuCard => toggle_card(&mut self.gpu_card_expanded),
+            MainWindowMessage::ToggleStorageCard => toggle_card(&mut self.storage_card_expanded),
+            MainWindowMessage::ToggleMotherboardCard => {
+                toggle_card(&mut self.motherboard_card_expanded)
+            }
+            MainWindowMessage::ToggleNetworkCard => toggle_card(&mut self.network_card_expanded),
+            MainWindowMessage::ToggleBatteryCard => toggle_card(&mut self.battery_card_expanded),
+            MainWindowMessage::TogglePsuCard => toggle_card(&mut self.psu_card_expanded),
             MainWindowMessage::Tick => {
                 // Update current time on each frame
                 self.now = Instant::now();
@@ -79,6 +135,13 @@ impl MainWindow {
         // Only subscribe to frames when animations are active
         if self.general_info_expanded.in_progress(self.now)
             || self.cores_card_expanded.in_progress(self.now)
+            || self.memory_card_expanded.in_progress(self.now)
+            || self.gpu_card_expanded.in_progress(self.now)
+            || self.storage_card_expanded.in_progress(self.now)
+            || self.motherboard_card_expanded.in_progress(self.now)
+            || self.network_card_expanded.in_progress(self.now)
+            || self.battery_card_expanded.in_progress(self.now)
+            || self.psu_card_expanded.in_progress(self.now)
         {
             window::frames().map(|_| MainWindowMessage::Tick)
         } else {
@@ -86,7 +149,17 @@ impl MainWindow {
         }
     }
 
-    pub fn view<'a>(&self, cpu_data: &'a CpuData) -> Element<'a, MainWindowMessage> {
+    pub fn view<'a>(
+        &self,
+        cpu_data: &'a CpuData,
+        memory: &'a MemoryData,
+        gpus: &'a [GpuData],
+        drives: &'a [StorageData],
+        motherboard: &'a MotherboardData,
+        network: &'a [InterfaceData],
+        batteries: &'a [BatteryData],
+        psus: &'a [PsuData],
+    ) -> Element<'a, MainWindowMessage> {
         let core_usage_vector = &cpu_data.core_utilization;
         let core_power_draw_vector = &cpu_data.core_power_draw;
 
@@ -94,12 +167,14 @@ impl MainWindow {
         General CPU info card
         */
 
-        // Animate height between collapsed (50px) and expanded (260px)
+        // Animate height between collapsed (50px) and expanded (260px, +40px for socket row)
         // 1.0 = expanded, 0.0 = collapsed
+        let multi_socket = cpu_data.packages.len() > 1;
+        let general_expanded_height = if multi_socket { 300.0 } else { 260.0 };
         let animation_factor = self
             .general_info_expanded
             .animate(std::convert::identity, self.now);
-        let general_card_height = 50.0 + (animation_factor * (260.0 - 50.0));
+        let general_card_height = 50.0 + (animation_factor * (general_expanded_height - 50.0));
         let is_general_expanded = self.general_info_expanded.value > 0.5;
 
         // Clickable header
@@ -143,32 +218,59 @@ impl MainWindow {
             .align_x(Center)
             .width(195);
 
-            let temp = column![
-                text("TEMP").size(20),
-                rich_text![
-                    span(format!("{:.1}", cpu_data.temp)).size(55),
-                    span(" \u{00B0}").size(38).font(Font {
-                        weight: font::Weight::Light,
-                        ..Font::default()
-                    }),
-                    span("C")
-                        .font(Font {
+            // Sensor poll failed, showing the last good values
+            let temp_title = if cpu_data.stale {
+                "TEMP (STALE)"
+            } else {
+                "TEMP"
+            };
+            let temp = if cpu_data.temp_available {
+                column![
+                    text(temp_title).size(20),
+                    rich_text![
+                        span(format!("{:.1}", cpu_data.temp)).size(55),
+                        span(" \u{00B0}").size(38).font(Font {
                             weight: font::Weight::Light,
                             ..Font::default()
-                        })
-                        .size(35),
+                        }),
+                        span("C")
+                            .font(Font {
+                                weight: font::Weight::Light,
+                                ..Font::default()
+                            })
+                            .size(35),
+                    ]
+                    .on_link_click(never),
+                    row![
+                        text(format!("L: {:.2}°C", cpu_data.temp_low)).size(20),
+                        text(" | ").size(20),
+                        text(format!("H: {:.2}°C", cpu_data.temp_high)).size(20),
+                    ]
+                    .spacing(5)
                 ]
-                .on_link_click(never),
-                row![
-                    text(format!("L: {:.2}°C", cpu_data.temp_low)).size(20),
-                    text(" | ").size(20),
-                    text(format!("H: {:.2}°C", cpu_data.temp_high)).size(20),
+            } else {
+                // No temperature source (LHM service not connected, no hwmon driver)
+                column![
+                    text("TEMP").size(20),
+                    text("Unavailable").size(38),
+                    text(NO_TEMP_SOURCE).size(14),
                 ]
-                .spacing(5)
-            ]
+                .spacing(10)
+            }
             .align_x(Center)
             .width(215);
 
+            let package_power = if cpu_data.power_available {
+                format!("{:.1} W", cpu_data.total_power_draw)
+            } else {
+                "Unavailable".to_string()
+            };
+            let power_title = if cpu_data.power_available && cpu_data.stale {
+                "PACKAGE POWER (STALE)"
+            } else {
+                "PACKAGE POWER"
+            };
+
             let clock_speed = column![
                 text("CLOCK SPEED").size(18),
                 text(format!("{:.0} MHz", cpu_data.current_frequency * 1000.0)).size(38),
@@ -178,8 +280,8 @@ impl MainWindow {
                     bottom: 8.0,
                     left: 0.0,
                 }),
-                text("PACKAGE POWER").size(18),
-                text(format!("{:.1} W", cpu_data.total_power_draw)).size(38)
+                text(power_title).size(18),
+                text(package_power).size(38)
             ]
             .align_x(Center)
             .width(190);
@@ -200,13 +302,36 @@ impl MainWindow {
                 left: 0.0,
             });
 
-            column![general_header_button, rule::horizontal(1), stats_row]
+            let mut general_column = column![general_header_button, rule::horizontal(1), stats_row]
                 .align_x(Center)
-                .spacing(15)
+                .spacing(15);
+
+            // Per-socket readings on multi-socket systems
+            if multi_socket {
+                let sockets = cpu_data.packages.iter().map(|package| {
+                    let power = package
+                        .power_draw
+                        .map(|w| format!("{:.1} W", w))
+                        .unwrap_or_else(|| "-- W".to_string());
+                    text(format!(
+                        "{}: {:.1}°C | {}",
+                        package.name, package.temp, power
+                    ))
+                    .size(16)
+                    .into()
+                });
+                general_column = general_column.push(Row::with_children(sockets).spacing(30));
+            }
+            general_column
         } else {
             // Collapsed view - show header with key metrics in one line
+            let collapsed_temp = if cpu_data.temp_available {
+                format!("{}°C", cpu_data.temp as i32)
+            } else {
+                "--°C".to_string()
+            };
             let collapsed_info = row![
-                text(format!("{}°C", cpu_data.temp as i32)).size(25),
+                text(collapsed_temp).size(25),
                 text("|").size(25),
                 text(format!("{:.1}%", cpu_data.usage)).size(25),
             ]
@@ -231,81 +356,18 @@ impl MainWindow {
             .style(styles::card_container_style)
             .clip(true);
 
-        /*
-          CORE USAGE COLUMNS
-        */
-
-        // Build core row with vertical rules between cores
-        let mut usage_bar_chart: Vec<Element<MainWindowMessage>> = Vec::new();
-        for (i, core) in core_usage_vector.iter().enumerate() {
-            let utilization = progress_bar(0.0..=100.0, core.value)
-                .vertical()
-                .length(150)
-                .girth(28);
-
-            let name_util_val = rich_text![
-                span(format!("{:.2}%\n", core.value))
-                    .font(Font {
-                        weight: font::Weight::Thin,
-                        ..Font::default()
-                    })
-                    .size(15),
-                span(core.name.to_string())
-                    .font(Font {
-                        weight: font::Weight::Thin,
-                        ..Font::default()
-                    })
-                    .size(15),
-            ]
-            .on_link_click(never)
-            .align_x(Center)
-            .width(55);
-            let core_col = column![utilization, name_util_val].align_x(Center);
-            usage_bar_chart.push(core_col.into());
-
-            // Add vertical rule between cores but not after the last one
-            if i < core_usage_vector.len() - 1 {
-                usage_bar_chart.push(rule::vertical(1).into());
-            }
-        }
-
-        /*
-          CORE POWER DRAW COLUMNS
-        */
-        let mut power_bar_chart: Vec<Element<MainWindowMessage>> = Vec::new();
-        for (i, core) in core_power_draw_vector.iter().enumerate() {
-            let wattage_bar = progress_bar(0.0..=20.0, core.value)
-                .vertical()
-                .length(150)
-                .girth(28);
-
-            let name_util_val = rich_text![
-                span(format!("{:.2}W\n", core.value))
-                    .font(Font {
-                        weight: font::Weight::Thin,
-                        ..Font::default()
-                    })
-                    .size(15),
-                span(core.name.replace("#", "").to_string())
-                    .font(Font {
-                        weight: font::Weight::Thin,
-                        ..Font::default()
-                    })
-                    .size(15),
-            ]
-            .on_link_click(never)
-            .align_x(Center)
-            .width(55);
-            let core_col = column![wattage_bar, name_util_val].align_x(Center);
-            power_bar_chart.push(core_col.into());
-
-            // Add vertical rule between cores but not after the last one
-            if i < core_usage_vector.len() - 1 {
-                power_bar_chart.push(rule::vertical(1).into());
-            }
-        }
-        let core_usage_row = Row::with_children(usage_bar_chart).spacing(1);
-        let core_power_row = Row::with_children(power_bar_chart).spacing(1);
+        let topology = &cpu_data.topology;
+        let core_usage_row = core_bar_chart(core_usage_vector, topology, 100.0, "%");
+        let core_power_row = core_bar_chart(core_power_draw_vector, topology, 20.0, "W");
+        let core_temp_row = core_bar_chart(&cpu_data.core_temps, topology, 100.0, "°C");
+        // Scale to the fastest core, boost clocks can be well above base
+        let max_frequency = cpu_data
+            .core_frequencies
+            .iter()
+            .map(|core| core.value)
+            .fold(cpu_data.base_cpu_frequency as f32, f32::max);
+        let core_frequency_row =
+            core_bar_chart(&cpu_data.core_frequencies, topology, max_frequency, "GHz");
 
         /*
           Cores card with collapse functionality
@@ -348,6 +410,34 @@ impl MainWindow {
         .on_press(MainWindowMessage::PowerButtonPressed)
         .style(styles::compact_icon_button_style);
 
+        let temp_button = button(
+            container(
+                svg(svg::Handle::from_path("assets/icons/thermometer.svg"))
+                    .width(25)
+                    .height(25),
+            )
+            .align_x(Center)
+            .align_y(Center)
+            .width(25)
+            .height(25),
+        )
+        .on_press(MainWindowMessage::TemperatureButtonPressed)
+        .style(styles::compact_icon_button_style);
+
+        let frequency_button = button(
+            container(
+                svg(svg::Handle::from_path("assets/icons/gauge.svg"))
+                    .width(25)
+                    .height(25),
+            )
+            .align_x(Center)
+            .align_y(Center)
+            .width(25)
+            .height(25),
+        )
+        .on_press(MainWindowMessage::FrequencyButtonPressed)
+        .style(styles::compact_icon_button_style);
+
         // Clickable header
         let cores_header_button = button(text("CORES").size(15).font(Font {
             weight: font::Weight::Bold,
@@ -359,10 +449,16 @@ impl MainWindow {
 
         let cores_card_content = if is_cores_expanded {
             // Expanded view - show full progress bars
-            let header_row = row![cores_header_button, usage_button, power_button,]
-                .align_y(Center)
-                .spacing(8)
-                .width(Fill);
+            let header_row = row![
+                cores_header_button,
+                usage_button,
+                power_button,
+                temp_button,
+                frequency_button
+            ]
+            .align_y(Center)
+            .spacing(8)
+            .width(Fill);
 
             column![
                 header_row,
@@ -370,6 +466,8 @@ impl MainWindow {
                 match self.bar_chart_state {
                     BarChartState::Usage => core_usage_row,
                     BarChartState::Power => core_power_row,
+                    BarChartState::Temperature => core_temp_row,
+                    BarChartState::Frequency => core_frequency_row,
                 }
             ]
             .align_x(Center)
@@ -380,6 +478,8 @@ impl MainWindow {
             let mode_text = match self.bar_chart_state {
                 BarChartState::Usage => "Usage",
                 BarChartState::Power => "Power",
+                BarChartState::Temperature => "Temperature",
+                BarChartState::Frequency => "Frequency",
             };
 
             let collapsed_info = row![
@@ -394,6 +494,8 @@ impl MainWindow {
                 collapsed_info,
                 usage_button,
                 power_button,
+                temp_button,
+                frequency_button,
             ]
             .align_y(Center)
             .spacing(8)
@@ -408,7 +510,574 @@ impl MainWindow {
             .style(styles::card_container_style)
             .clip(true);
 
-        let all_cards = column![general_cpu_info_card, cores_card].spacing(20);
+        let mut all_cards = column![general_cpu_info_card, cores_card].spacing(20);
+        // Battery card only on laptops
+        if !batteries.is_empty() {
+            all_cards = all_cards.push(self.battery_card(batteries));
+        }
+        if memory.total > 0.0 {
+            all_cards = all_cards.push(self.memory_card(memory));
+        }
+        // GPU card only when LHM reports at least one GPU
+        if !gpus.is_empty() {
+            all_cards = all_cards.push(self.gpu_card(gpus));
+        }
+        if !drives.is_empty() {
+            all_cards = all_cards.push(self.storage_card(drives));
+        }
+        if !motherboard.fans.is_empty() || !motherboard.voltages.is_empty() {
+            all_cards = all_cards.push(self.motherboard_card(motherboard));
+        }
+        if !psus.is_empty() {
+            all_cards = all_cards.push(self.psu_card(cpu_data, psus));
+        }
+        if !network.is_empty() {
+            all_cards = all_cards.push(self.network_card(network));
+        }
         container(all_cards).padding(20).width(Fill).into()
     }
+
+    /// Collapsible card used by the hardware cards (GPU, storage, ...).
+    /// `body` is shown below the header when expanded, `summary` next to it when collapsed
+    fn hardware_card<'a>(
+        &self,
+        expanded: &Animated<f32, Instant>,
+        title: &'a str,
+        on_toggle: MainWindowMessage,
+        expanded_height: f32,
+        body: impl FnOnce() -> Element<'a, MainWindowMessage>,
+        summary: impl FnOnce() -> Element<'a, MainWindowMessage>,
+    ) -> Element<'a, MainWindowMessage> {
+        // Animate height between collapsed (50px) and expanded_height
+        let animation_factor = expanded.animate(std::convert::identity, self.now);
+        let card_height = 50.0 + (animation_factor * (expanded_height - 50.0));
+        let is_expanded = expanded.value > 0.5;
+
+        let header_button = button(text(title).size(15).font(Font {
+            weight: font::Weight::Bold,
+            ..Font::default()
+        }))
+        .on_press(on_toggle)
+        .width(Fill)
+        .style(styles::header_button_style);
+
+        let content = if is_expanded {
+            column![header_button, rule::horizontal(1), body()]
+                .spacing(10)
+                .align_x(Center)
+                .padding(10)
+        } else {
+            column![row![header_button, summary()]
+                .align_y(Center)
+                .spacing(8)
+                .width(Fill)]
+            .padding(10)
+        };
+
+        container(content)
+            .width(Fill)
+            .height(card_height)
+            .align_x(Center)
+            .style(styles::card_container_style)
+            .clip(true)
+            .into()
+    }
+
+    /*
+      GPU card, one stats row per GPU
+    */
+    fn gpu_card<'a>(&self, gpus: &'a [GpuData]) -> Element<'a, MainWindowMessage> {
+        let body = || -> Element<'a, MainWindowMessage> {
+            let mut gpu_rows = column![].spacing(10).align_x(Center);
+            for gpu in gpus {
+                let vram = match (gpu.vram_used, gpu.vram_total) {
+                    (Some(used), Some(total)) => {
+                        format!("{:.1} / {:.1} GB", used / 1024.0, total / 1024.0)
+                    }
+                    (Some(used), None) => format!("{:.1} GB", used / 1024.0),
+                    _ => "--".to_string(),
+                };
+                let stats = row![
+                    stat_column("CORE", format_value(gpu.core_temp, "°C", 0)),
+                    stat_column("HOT SPOT", format_value(gpu.hotspot_temp, "°C", 0)),
+                    stat_column("MEMORY", format_value(gpu.memory_temp, "°C", 0)),
+                    rule::vertical(1),
+                    stat_column("LOAD", format_value(gpu.load, "%", 0)),
+                    stat_column("POWER", format_value(gpu.power_draw, " W", 1)),
+                    stat_column("VRAM", vram),
+                    stat_column("CLOCK", format_value(gpu.core_clock, " MHz", 0)),
+                ]
+                .spacing(15)
+                .height(60)
+                .align_y(Center);
+
+                gpu_rows = gpu_rows.push(
+                    column![text(&gpu.name).size(16), stats]
+                        .spacing(8)
+                        .align_x(Center),
+                );
+            }
+            gpu_rows.into()
+        };
+        // Collapsed view - first GPU temperature and load in one line
+        let summary = || -> Element<'a, MainWindowMessage> {
+            let first = &gpus[0];
+            row![
+                text(format_value(first.core_temp, "°C", 0)).size(14),
+                text("|").size(14),
+                text(format_value(first.load, "%", 0)).size(14),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        self.hardware_card(
+            &self.gpu_card_expanded,
+            "GPU",
+            MainWindowMessage::ToggleGpuCard,
+            60.0 + 110.0 * gpus.len() as f32,
+            body,
+            summary,
+        )
+    }
+
+    /*
+      Memory card, RAM and swap usage, top memory consumers and DIMM temperatures
+    */
+    fn memory_card<'a>(&self, memory: &'a MemoryData) -> Element<'a, MainWindowMessage> {
+        let body = || -> Element<'a, MainWindowMessage> {
+            let swap = if memory.swap_total > 0.0 {
+                format!("{:.1}/{:.1} GB", memory.swap_used, memory.swap_total)
+            } else {
+                "--".to_string()
+            };
+            let stats = row![
+                stat_column("USED", format!("{:.1}/{:.1} GB", memory.used, memory.total)),
+                stat_column("AVAILABLE", format!("{:.1} GB", memory.available)),
+                stat_column("SWAP", swap),
+            ]
+            .spacing(30)
+            .height(55)
+            .align_y(Center);
+
+            let processes = memory.top_processes.iter().map(|process| {
+                let process_row: Element<'a, MainWindowMessage> = row![
+                    text(&process.name).size(14).width(Fill),
+                    text(format!("{:.0} MB", process.memory)).size(14),
+                ]
+                .into();
+                process_row
+            });
+            let mut content = column![
+                stats,
+                rule::horizontal(1),
+                text("TOP PROCESSES").size(13),
+                Column::with_children(processes).spacing(2),
+            ]
+            .spacing(8);
+
+            // DDR4 modules often have no temperature sensor
+            if !memory.dimm_temps.is_empty() {
+                let dimms = memory
+                    .dimm_temps
+                    .iter()
+                    .map(|dimm| stat_column(&dimm.name, format!("{:.0}°C", dimm.temp)));
+                content = content
+                    .push(rule::horizontal(1))
+                    .push(Row::with_children(dimms).spacing(20));
+            }
+            content.into()
+        };
+        // Collapsed view - RAM usage
+        let summary = || -> Element<'a, MainWindowMessage> {
+            row![
+                text(format!("{:.0}%", memory.usage_percent())).size(14),
+                text("|").size(14),
+                text(format!("{:.1} GB free", memory.available)).size(14),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        let dimm_height = if memory.dimm_temps.is_empty() {
+            0.0
+        } else {
+            70.0
+        };
+        self.hardware_card(
+            &self.memory_card_expanded,
+            "MEMORY",
+            MainWindowMessage::ToggleMemoryCard,
+            150.0 + 20.0 * memory.top_processes.len() as f32 + dimm_height,
+            body,
+            summary,
+        )
+    }
+
+    /*
+      Storage card, one row per drive
+    */
+    fn storage_card<'a>(&self, drives: &'a [StorageData]) -> Element<'a, MainWindowMessage> {
+        let body = || -> Element<'a, MainWindowMessage> {
+            let mut drive_rows = column![].spacing(10);
+            for drive in drives {
+                let written = match drive.data_written {
+                    Some(gb) if gb >= 1024.0 => format!("{:.1} TB", gb / 1024.0),
+                    Some(gb) => format!("{:.0} GB", gb),
+                    None => "--".to_string(),
+                };
+                let drive_row = row![
+                    text(&drive.name).size(15).width(Fill),
+                    stat_column("TEMP", format_value(drive.temp, "°C", 0)),
+                    stat_column("USED", format_value(drive.used_space, "%", 0)),
+                    stat_column("LIFE", format_value(drive.remaining_life, "%", 0)),
+                    stat_column("SPARE", format_value(drive.available_spare, "%", 0)),
+                    stat_column("WRITTEN", written),
+                ]
+                .spacing(20)
+                .height(55)
+                .align_y(Center);
+                drive_rows = drive_rows.push(drive_row);
+            }
+            drive_rows.into()
+        };
+        // Collapsed view - hottest drive
+        let summary = || -> Element<'a, MainWindowMessage> {
+            let hottest = drives.iter().filter_map(|d| d.temp).reduce(f32::max);
+            row![
+                text(format!("{} drives", drives.len())).size(14),
+                text("|").size(14),
+                text(format!("max {}", format_value(hottest, "°C", 0))).size(14),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        self.hardware_card(
+            &self.storage_card_expanded,
+            "STORAGE",
+            MainWindowMessage::ToggleStorageCard,
+            60.0 + 65.0 * drives.len() as f32,
+            body,
+            summary,
+        )
+    }
+
+    /*
+      Motherboard card, fan speeds and voltage rails
+    */
+    fn motherboard_card<'a>(
+        &self,
+        motherboard: &'a MotherboardData,
+    ) -> Element<'a, MainWindowMessage> {
+        let body = || -> Element<'a, MainWindowMessage> {
+            let fans = motherboard.fans.iter().map(|fan| {
+                let control = fan
+                    .control
+                    .map(|duty| format!("\n{:.0}%", duty))
+                    .unwrap_or_default();
+                stat_column(&fan.name, format!("{:.0} RPM{}", fan.rpm, control))
+            });
+            let voltages = motherboard.voltages.iter().map(|rail| {
+                let warning = if rail.out_of_spec() { " ⚠" } else { "" };
+                stat_column(&rail.name, format!("{:.3} V{}", rail.volts, warning))
+            });
+
+            column![
+                text("FANS").size(13),
+                Row::with_children(fans).spacing(20),
+                rule::horizontal(1),
+                text("VOLTAGES").size(13),
+                Row::with_children(voltages).spacing(20),
+            ]
+            .spacing(8)
+            .into()
+        };
+        // Collapsed view - fan count and warnings
+        let summary = || -> Element<'a, MainWindowMessage> {
+            let warnings = motherboard.warnings().len();
+            row![
+                text(format!("{} fans", motherboard.fans.len())).size(14),
+                text("|").size(14),
+                text(format!("{} warnings", warnings)).size(14),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        self.hardware_card(
+            &self.motherboard_card_expanded,
+            "MOTHERBOARD",
+            MainWindowMessage::ToggleMotherboardCard,
+            260.0,
+            body,
+            summary,
+        )
+    }
+
+    /*
+      Battery card, one row per battery
+    */
+    fn battery_card<'a>(&self, batteries: &'a [BatteryData]) -> Element<'a, MainWindowMessage> {
+        let body = || -> Element<'a, MainWindowMessage> {
+            let mut battery_rows = column![].spacing(10);
+            for battery in batteries {
+                let source = if battery.on_battery { "BATTERY" } else { "AC" };
+                let battery_row = row![
+                    text(&battery.name).size(15).width(Fill),
+                    stat_column("CHARGE", format_value(battery.charge_level, "%", 0)),
+                    stat_column("RATE", format_value(battery.rate, " W", 1)),
+                    stat_column("WEAR", format_value(battery.wear_level, "%", 1)),
+                    stat_column("SOURCE", source.to_string()),
+                ]
+                .spacing(20)
+                .height(55)
+                .align_y(Center);
+                battery_rows = battery_rows.push(battery_row);
+            }
+            battery_rows.into()
+        };
+        // Collapsed view - first battery charge and rate
+        let summary =
+            || -> Element<'a, MainWindowMessage> { text(batteries[0].summary()).size(14).into() };
+
+        self.hardware_card(
+            &self.battery_card_expanded,
+            "BATTERY",
+            MainWindowMessage::ToggleBatteryCard,
+            60.0 + 65.0 * batteries.len() as f32,
+            body,
+            summary,
+        )
+    }
+
+    /*
+      PSU card, one row per PSU and CPU package power against wall draw
+    */
+    fn psu_card<'a>(
+        &self,
+        cpu_data: &'a CpuData,
+        psus: &'a [PsuData],
+    ) -> Element<'a, MainWindowMessage> {
+        let cpu_power = cpu_data
+            .power_available
+            .then_some(cpu_data.total_power_draw);
+        let wall_draw = total_wall_draw(psus);
+        let cpu_share = cpu_power.and_then(|power| cpu_share_of_wall(power, wall_draw));
+
+        let body = || -> Element<'a, MainWindowMessage> {
+            let mut psu_rows = column![].spacing(10);
+            for psu in psus {
+                let psu_row = row![
+                    text(&psu.name).size(15).width(Fill),
+                    stat_column("INPUT", format_value(psu.input_power, " W", 0)),
+                    stat_column("OUTPUT", format_value(psu.output_power, " W", 0)),
+                    stat_column("EFFICIENCY", format_value(psu.efficiency, "%", 1)),
+                    stat_column("FAN", format_value(psu.fan_rpm, " RPM", 0)),
+                    stat_column("TEMP", format_value(psu.temp, "°C", 0)),
+                ]
+                .spacing(20)
+                .height(55)
+                .align_y(Center);
+                psu_rows = psu_rows.push(psu_row);
+            }
+            let comparison = row![
+                stat_column("CPU PACKAGE", format_value(cpu_power, " W", 1)),
+                stat_column("WALL", format_value(wall_draw, " W", 1)),
+                stat_column("CPU SHARE", format_value(cpu_share, "%", 0)),
+            ]
+            .spacing(30)
+            .height(55)
+            .align_y(Center);
+            psu_rows.push(rule::horizontal(1)).push(comparison).into()
+        };
+        // Collapsed view - wall draw and CPU share
+        let summary = || -> Element<'a, MainWindowMessage> {
+            row![
+                text(format!("{} wall", format_value(wall_draw, " W", 0))).size(14),
+                text("|").size(14),
+                text(format!("CPU {}", format_value(cpu_share, "%", 0))).size(14),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        self.hardware_card(
+            &self.psu_card_expanded,
+            "PSU",
+            MainWindowMessage::TogglePsuCard,
+            130.0 + 65.0 * psus.len() as f32,
+            body,
+            summary,
+        )
+    }
+
+    /*
+      Network card, one row per interface
+    */
+    fn network_card<'a>(&self, network: &'a [InterfaceData]) -> Element<'a, MainWindowMessage> {
+        let body = || -> Element<'a, MainWindowMessage> {
+            let mut interface_rows = column![].spacing(10);
+            for interface in network {
+                let interface_row = row![
+                    text(&interface.name).size(15).width(Fill),
+                    stat_column("DOWN", format_rate(interface.rx_rate)),
+                    stat_column("UP", format_rate(interface.tx_rate)),
+                    stat_column("RECEIVED", format_bytes(interface.rx_total)),
+                    stat_column("SENT", format_bytes(interface.tx_total)),
+                    stat_column(
+                        "ERRORS",
+                        (interface.rx_errors + interface.tx_errors).to_string()
+                    ),
+                ]
+                .spacing(20)
+                .height(55)
+                .align_y(Center);
+                interface_rows = interface_rows.push(interface_row);
+            }
+            interface_rows.into()
+        };
+        // Collapsed view - throughput over all interfaces
+        let summary = || -> Element<'a, MainWindowMessage> {
+            let rx_rate: f64 = network.iter().map(|i| i.rx_rate).sum();
+            let tx_rate: f64 = network.iter().map(|i| i.tx_rate).sum();
+            row![
+                text(format!("↓ {}", format_rate(rx_rate))).size(14),
+                text("|").size(14),
+                text(format!("↑ {}", format_rate(tx_rate))).size(14),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        self.hardware_card(
+            &self.network_card_expanded,
+            "NETWORK",
+            MainWindowMessage::ToggleNetworkCard,
+            60.0 + 65.0 * network.len() as f32,
+            body,
+            summary,
+        )
+    }
+}
+
+/// Vertical bar per core with the value and core name below it, rules between cores.
+/// Cores are grouped by type and cluster, each group labeled with its average and max
+fn core_bar_chart<'a>(
+    cores: &[CoreStats],
+    topology: &CpuTopology,
+    max: f32,
+    unit: &str,
+) -> Row<'a, MainWindowMessage> {
+    let entries = topology.entries(cores.len());
+    let groups = topology.groups(cores.len());
+    let stats = topology.aggregate(cores);
+
+    let mut bar_chart: Vec<Element<MainWindowMessage>> = Vec::new();
+    for (group_index, group) in groups.iter().enumerate() {
+        let mut bars: Vec<Element<MainWindowMessage>> = Vec::new();
+        for (i, position) in group.members.iter().enumerate() {
+            let core = &cores[*position];
+            let smt_sibling = entries
+                .as_ref()
+                .is_some_and(|entries| entries[*position].smt_sibling);
+            let bar = progress_bar(0.0..=max, core.value)
+                .vertical()
+                .length(150)
+                .girth(28)
+                .style(styles::core_bar_style(group.core_type, smt_sibling));
+
+            let name_value = rich_text![
+                span(format!("{:.2}{}\n", core.value, unit))
+                    .font(Font {
+                        weight: font::Weight::Thin,
+                        ..Font::default()
+                    })
+                    .size(15),
+                span(core.name.replace("#", ""))
+                    .font(Font {
+                        weight: font::Weight::Thin,
+                        ..Font::default()
+                    })
+                    .size(15),
+            ]
+            .on_link_click(never)
+            .align_x(Center)
+            .width(55);
+            let core_col = column![bar, name_value].align_x(Center);
+            bars.push(core_col.into());
+
+            // Add vertical rule between cores but not after the last one
+            if i < group.members.len() - 1 {
+                bars.push(rule::vertical(1).into());
+            }
+        }
+
+        // Only label groups when there's more than one
+        let mut group_col = Column::new().align_x(Center).spacing(4);
+        if groups.len() > 1 {
+            let summary = stats
+                .iter()
+                .find(|s| s.label == group.label)
+                .map(|s| {
+                    format!(
+                        "{}  avg {:.1}{u}  max {:.1}{u}",
+                        s.label,
+                        s.average,
+                        s.max,
+                        u = unit
+                    )
+                })
+                .unwrap_or_else(|| group.label.clone());
+            group_col = group_col.push(text(summary).size(13));
+        }
+        bar_chart.push(group_col.push(Row::with_children(bars).spacing(1)).into());
+
+        if group_index < groups.len() - 1 {
+            bar_chart.push(rule::vertical(3).into());
+        }
+    }
+    Row::with_children(bar_chart).spacing(6)
+}
+
+/// Flip a card between collapsed (0.0) and expanded (1.0)
+fn toggle_card(card: &mut Animated<f32, Instant>) {
+    let new_value = if card.value > 0.5 { 0.0 } else { 1.0 };
+    card.transition(new_value, Instant::now());
+}
+
+/// Small labeled value used in the hardware cards
+fn stat_column<'a>(label: &'a str, value: String) -> Element<'a, MainWindowMessage> {
+    column![text(label).size(13), text(value).size(20)]
+        .align_x(Center)
+        .spacing(4)
+        .into()
+}
+
+/// Format an optional sensor value, "--" when the sensor isn't available
+fn format_value(value: Option<f32>, unit: &str, decimals: usize) -> String {
+    match value {
+        Some(v) => format!("{:.*}{}", decimals, v, unit),
+        None => "--".to_string(),
+    }
+}
+
+/// Format a byte count using the largest fitting unit
+fn format_bytes(bytes: u64) -> String {
+    let mb = bytes as f64 / f64::from(BYTES_PER_MB);
+    if mb >= 1024.0 {
+        format!("{:.2} GB", mb / 1024.0)
+    } else {
+        format!("{:.1} MB", mb)
+    }
+}
+
+/// Format a throughput in bytes per second
+fn format_rate(bytes_per_sec: f64) -> String {
+    let kb = bytes_per_sec / 1024.0;
+    if kb >= 1024.0 {
+        format!("{:.1} MB/s", kb / 1024.0)
+    } else {
+        format!("{:.0} KB/s", kb)
+    }
 }
@@ -2,5 +2,8 @@ pub mod layout;
 pub mod main_window;
 pub mod modal;
 pub mod plot_window;
+pub mod process_window;
 pub mod settings;
 pub mod styles;
+pub mod tray_menu;
+pub mod tray_renderer;
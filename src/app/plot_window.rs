@@ -1,76 +1,133 @@
-use crate::utils::csv_logger::CsvLogger;
+use crate::utils::csv_logger::{CsvCpuLogEntry, CsvLogger};
+use crate::utils::sqlite_store::{self, Aggregate, Family, SqliteStore};
+use chrono::Local;
+use iced::widget::{column, pick_list, row, text};
 use iced::{Color, Element};
 use iced_plot::{LineStyle, MarkerStyle, PlotWidget, PlotWidgetBuilder, Series};
+use std::fmt;
+use std::time::Duration;
+
+const CPU_TEMP_LABEL: &str = "CPU Temperature";
+const NETWORK_RX_LABEL: &str = "Network down (MB/s)";
+const NETWORK_TX_LABEL: &str = "Network up (MB/s)";
+const CPU_TEMP_AVG_LABEL: &str = "CPU Temperature (average)";
+const CPU_TEMP_MAX_LABEL: &str = "CPU Temperature (max)";
+const MS_PER_HOUR: f64 = 3_600_000.0;
+
+/// Time span shown in the plot. Live plots the current log buffer, the others
+/// read the SQLite history across days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlotRange {
+    Live,
+    Day,
+    Week,
+}
+
+impl PlotRange {
+    pub const ALL: [PlotRange; 3] = [PlotRange::Live, PlotRange::Day, PlotRange::Week];
+
+    fn hours(&self) -> i64 {
+        match self {
+            PlotRange::Live => 0,
+            PlotRange::Day => 24,
+            PlotRange::Week => 24 * 7,
+        }
+    }
+
+    // A few hundred points per range
+    fn bucket(&self) -> Duration {
+        match self {
+            PlotRange::Live => Duration::from_secs(1),
+            PlotRange::Day => Duration::from_secs(5 * 60),
+            PlotRange::Week => Duration::from_secs(30 * 60),
+        }
+    }
+}
+
+impl fmt::Display for PlotRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlotRange::Live => write!(f, "Live"),
+            PlotRange::Day => write!(f, "Last 24 hours"),
+            PlotRange::Week => write!(f, "Last 7 days"),
+        }
+    }
+}
 
 pub struct PlotWindow {
     plot: PlotWidget,
+    range: PlotRange,
+    // Why the history can't be shown
+    history_error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub enum PlotWindowMessage {
     PlotUiMessage(iced_plot::PlotUiMessage),
     Tick,
+    RangeSelected(PlotRange),
 }
 
 impl PlotWindow {
     pub fn new() -> Self {
-        // Initial dummy series to prevent empty buffer panics
-        let dummy_series = Series::circles(vec![[0.0, 0.0]], 3.0).with_label("waiting for data");
-
         Self {
-            plot: PlotWidgetBuilder::new()
-                .with_autoscale_on_updates(true)
-                .with_y_label("Temperature (°C)")
-                .with_x_label("Time (s)")
-                .with_tooltips(true)
-                .with_x_lim(0.0, 60.0)
-                .with_y_lim(0.0, 100.0)
-                .add_series(dummy_series)
-                .build()
-                .unwrap(),
+            plot: live_plot(),
+            range: PlotRange::Live,
+            history_error: None,
         }
     }
 
-    pub fn update(&mut self, csv_logger: &CsvLogger, message: PlotWindowMessage) {
+    /// `store` is only needed for the history ranges, `None` when SQLite storage is off
+    pub fn update(
+        &mut self,
+        csv_logger: &CsvLogger,
+        store: Option<&SqliteStore>,
+        message: PlotWindowMessage,
+    ) {
         match message {
             PlotWindowMessage::PlotUiMessage(msg) => {
                 self.plot.update(msg);
             }
+            PlotWindowMessage::RangeSelected(range) => {
+                self.range = range;
+                self.history_error = None;
+                if range == PlotRange::Live {
+                    self.plot = live_plot();
+                } else {
+                    self.load_history(store);
+                }
+            }
+            // History is loaded once when the range is picked
+            PlotWindowMessage::Tick if self.range != PlotRange::Live => {}
             PlotWindowMessage::Tick => {
-                let mut cpu_temp_series: Vec<[f64; 2]> = csv_logger
-                    .graph_data_buffer
-                    .iter()
-                    .enumerate()
-                    .map(|(i, entry)| {
-                        [
-                            i as f64, // X axis
-                            entry.temperature as f64,
-                        ]
-                    })
-                    .collect();
-
-                if !cpu_temp_series.is_empty() {
-                    // If we have fewer than 33 points, duplicate the last point until we do.
-                    // Workaround: Pad to 33 points to force wgpu buffer update.
-                    // Necessary to display points between 0 and 33
-                    if cpu_temp_series.len() < 33 {
-                        let last_point = *cpu_temp_series.last().unwrap();
-                        while cpu_temp_series.len() < 33 {
-                            cpu_temp_series.push(last_point);
-                        }
-                    }
-
-                    // Remove dummy/old series
-                    self.plot.remove_series("waiting for data");
-                    self.plot.remove_series("CPU Temperature");
-
-                    let temp_series =
-                        Series::new(cpu_temp_series, MarkerStyle::circle(2.0), LineStyle::Solid)
-                            .with_label("CPU Temperature")
-                            .with_color(Color::from_rgb(1.0, 0.5, 0.2));
-
-                    self.plot.add_series(temp_series).unwrap();
+                let cpu_temp_series = padded_points(csv_logger, |entry| entry.temperature);
+                if cpu_temp_series.is_empty() {
+                    return;
                 }
+                let rx_series = padded_points(csv_logger, |entry| Some(entry.network_rx_rate));
+                let tx_series = padded_points(csv_logger, |entry| Some(entry.network_tx_rate));
+
+                // Remove dummy/old series
+                self.plot.remove_series("waiting for data");
+                for label in [CPU_TEMP_LABEL, NETWORK_RX_LABEL, NETWORK_TX_LABEL] {
+                    self.plot.remove_series(label);
+                }
+
+                let temp_series =
+                    Series::new(cpu_temp_series, MarkerStyle::circle(2.0), LineStyle::Solid)
+                        .with_label(CPU_TEMP_LABEL)
+                        .with_color(Color::from_rgb(1.0, 0.5, 0.2));
+                // Network throughput on the same axis, to line up temperature spikes with transfers
+                let rx_series = Series::new(rx_series, MarkerStyle::circle(1.5), LineStyle::Solid)
+                    .with_label(NETWORK_RX_LABEL)
+                    .with_color(Color::from_rgb(0.2, 0.6, 1.0));
+                let tx_series = Series::new(tx_series, MarkerStyle::circle(1.5), LineStyle::Solid)
+                    .with_label(NETWORK_TX_LABEL)
+                    .with_color(Color::from_rgb(0.4, 0.9, 0.4));
+
+                self.plot.add_series(temp_series).unwrap();
+                self.plot.add_series(rx_series).unwrap();
+                self.plot.add_series(tx_series).unwrap();
             }
         }
     }
@@ -80,6 +137,131 @@ impl PlotWindow {
     }
 
     pub fn view(&self) -> Element<'_, PlotWindowMessage> {
-        self.plot.view().map(PlotWindowMessage::PlotUiMessage)
+        let mut controls = row![pick_list(
+            PlotRange::ALL,
+            Some(self.range),
+            PlotWindowMessage::RangeSelected
+        )]
+        .spacing(10);
+        if let Some(error) = &self.history_error {
+            controls = controls.push(text(error.as_str()).size(14));
+        }
+        column![
+            controls,
+            self.plot.view().map(PlotWindowMessage::PlotUiMessage)
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// Average and hottest CPU temperature per bucket from the SQLite history.
+    /// X is in hours relative to now
+    fn load_history(&mut self, store: Option<&SqliteStore>) {
+        let Some(store) = store else {
+            self.history_error =
+                Some("Turn on SQLite storage in settings to see older data".into());
+            self.plot = history_plot(self.range, Vec::new());
+            return;
+        };
+        let to = Local::now();
+        let from = to - chrono::Duration::hours(self.range.hours());
+        let temperature = sqlite_store::Series::new(Family::Cpu, "temperature");
+
+        let mut series = Vec::new();
+        for (aggregate, label, color) in [
+            (
+                Aggregate::Avg,
+                CPU_TEMP_AVG_LABEL,
+                Color::from_rgb(1.0, 0.5, 0.2),
+            ),
+            (
+                Aggregate::Max,
+                CPU_TEMP_MAX_LABEL,
+                Color::from_rgb(1.0, 0.2, 0.2),
+            ),
+        ] {
+            match store.downsample(&temperature, from, to, self.range.bucket(), aggregate) {
+                Ok(points) if !points.is_empty() => {
+                    let points = points
+                        .iter()
+                        .map(|point| {
+                            let hours =
+                                (point.timestamp - to.timestamp_millis()) as f64 / MS_PER_HOUR;
+                            [hours, point.value]
+                        })
+                        .collect();
+                    series.push(
+                        Series::new(points, MarkerStyle::circle(1.5), LineStyle::Solid)
+                            .with_label(label)
+                            .with_color(color),
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => self.history_error = Some(format!("History query failed: {}", e)),
+            }
+        }
+        self.plot = history_plot(self.range, series);
+    }
+}
+
+fn live_plot() -> PlotWidget {
+    // Initial dummy series to prevent empty buffer panics
+    let dummy_series = Series::circles(vec![[0.0, 0.0]], 3.0).with_label("waiting for data");
+
+    PlotWidgetBuilder::new()
+        .with_autoscale_on_updates(true)
+        .with_y_label("Temperature (°C)")
+        .with_x_label("Time (s)")
+        .with_tooltips(true)
+        .with_x_lim(0.0, 60.0)
+        .with_y_lim(0.0, 100.0)
+        .add_series(dummy_series)
+        .build()
+        .unwrap()
+}
+
+fn history_plot(range: PlotRange, series: Vec<Series>) -> PlotWidget {
+    let mut builder = PlotWidgetBuilder::new()
+        .with_autoscale_on_updates(true)
+        .with_y_label("Temperature (°C)")
+        .with_x_label("Hours ago")
+        .with_tooltips(true)
+        .with_x_lim(-(range.hours() as f64), 0.0)
+        .with_y_lim(0.0, 100.0);
+    if series.is_empty() {
+        // Same workaround as the live plot, an empty plot panics
+        builder = builder.add_series(Series::circles(vec![[0.0, 0.0]], 3.0).with_label("no data"));
+    }
+    for series in series {
+        builder = builder.add_series(series);
+    }
+    builder.build().unwrap()
+}
+
+/// One point per buffered log entry, entries without a value are left out
+fn padded_points(
+    csv_logger: &CsvLogger,
+    value: impl Fn(&CsvCpuLogEntry) -> Option<f32>,
+) -> Vec<[f64; 2]> {
+    let mut points: Vec<[f64; 2]> = csv_logger
+        .graph_data_buffer
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            Some([
+                i as f64, // X axis
+                value(entry)? as f64,
+            ])
+        })
+        .collect();
+
+    // If we have fewer than 33 points, duplicate the last point until we do.
+    // Workaround: Pad to 33 points to force wgpu buffer update.
+    // Necessary to display points between 0 and 33
+    if let Some(&last_point) = points.last() {
+        while points.len() < 33 {
+            points.push(last_point);
+        }
     }
+    points
 }
@@ -0,0 +1,217 @@
+use crate::app::styles;
+use crate::collectors::process_collector::ProcessData;
+use crate::utils::incident_logger::Incident;
+use iced::widget::{button, column, container, rule, scrollable, text, Column, Row};
+use iced::{font, Center, Element, Fill, Font, Length};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProcessColumn {
+    Name,
+    Pid,
+    Cpu,
+    Memory,
+    Power,
+}
+
+impl ProcessColumn {
+    const ALL: [ProcessColumn; 5] = [
+        ProcessColumn::Name,
+        ProcessColumn::Pid,
+        ProcessColumn::Cpu,
+        ProcessColumn::Memory,
+        ProcessColumn::Power,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            ProcessColumn::Name => "NAME",
+            ProcessColumn::Pid => "PID",
+            ProcessColumn::Cpu => "CPU",
+            ProcessColumn::Memory => "MEMORY",
+            ProcessColumn::Power => "POWER (EST.)",
+        }
+    }
+
+    fn width(&self) -> Length {
+        match self {
+            ProcessColumn::Name => Length::Fill,
+            ProcessColumn::Pid => Length::Fixed(80.0),
+            ProcessColumn::Cpu => Length::Fixed(80.0),
+            ProcessColumn::Memory => Length::Fixed(100.0),
+            ProcessColumn::Power => Length::Fixed(120.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ProcessWindowMessage {
+    SortBy(ProcessColumn),
+}
+
+/// Table of the top processes by CPU and memory, sortable by any column, and the
+/// high temperature incidents captured so far
+pub struct ProcessWindow {
+    sort_column: ProcessColumn,
+    descending: bool,
+}
+
+impl ProcessWindow {
+    pub fn new() -> Self {
+        Self {
+            sort_column: ProcessColumn::Cpu,
+            descending: true,
+        }
+    }
+
+    pub fn update(&mut self, message: ProcessWindowMessage) {
+        match message {
+            ProcessWindowMessage::SortBy(column) => {
+                if column == self.sort_column {
+                    self.descending = !self.descending;
+                } else {
+                    // Names sort A-Z first, numbers biggest first
+                    self.sort_column = column;
+                    self.descending = column != ProcessColumn::Name;
+                }
+            }
+        }
+    }
+
+    pub fn view<'a>(
+        &self,
+        processes: &'a [ProcessData],
+        incidents: &'a [Incident],
+    ) -> Element<'a, ProcessWindowMessage> {
+        let header = Row::with_children(ProcessColumn::ALL.iter().map(|column| {
+            let arrow = match (*column == self.sort_column, self.descending) {
+                (false, _) => "",
+                (true, true) => " ▼",
+                (true, false) => " ▲",
+            };
+            button(
+                text(format!("{}{}", column.title(), arrow))
+                    .size(14)
+                    .font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    }),
+            )
+            .on_press(ProcessWindowMessage::SortBy(*column))
+            .width(column.width())
+            .style(styles::header_button_style)
+            .into()
+        }))
+        .spacing(4)
+        .align_y(Center);
+
+        let mut sorted: Vec<&ProcessData> = processes.iter().collect();
+        sorted.sort_by(|a, b| {
+            let ordering = match self.sort_column {
+                ProcessColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                ProcessColumn::Pid => a.pid.cmp(&b.pid),
+                ProcessColumn::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+                ProcessColumn::Memory => a.memory.total_cmp(&b.memory),
+                ProcessColumn::Power => a
+                    .power_draw
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.power_draw.unwrap_or(0.0)),
+            };
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        let rows = Column::with_children(sorted.into_iter().map(|process| {
+            let cells = [
+                process.name.clone(),
+                process.pid.to_string(),
+                format!("{:.1}%", process.cpu_usage),
+                format!("{:.0} MB", process.memory),
+                process
+                    .power_draw
+                    .map(|power| format!("{:.1} W", power))
+                    .unwrap_or_else(|| "--".to_string()),
+            ];
+            Row::with_children(ProcessColumn::ALL.iter().zip(cells).map(|(column, cell)| {
+                // Same padding as the header buttons so the columns line up
+                container(text(cell).size(14))
+                    .padding([0, 10])
+                    .width(column.width())
+                    .into()
+            }))
+            .spacing(4)
+            .into()
+        }))
+        .spacing(6);
+
+        let content = if processes.is_empty() {
+            column![
+                header,
+                rule::horizontal(1),
+                text("Waiting for data").size(14)
+            ]
+        } else {
+            column![
+                header,
+                rule::horizontal(1),
+                scrollable(rows).style(styles::thin_scrollbar_style)
+            ]
+        };
+
+        let process_card = container(content.spacing(10).padding(10))
+            .width(Fill)
+            .style(styles::card_container_style);
+
+        container(column![process_card, incidents_card(incidents)].spacing(20))
+            .padding(20)
+            .width(Fill)
+            .into()
+    }
+}
+
+/// Captured incidents, newest first, with the processes that were running at the time
+fn incidents_card<'a>(incidents: &'a [Incident]) -> Element<'a, ProcessWindowMessage> {
+    let title = text("HIGH TEMPERATURE INCIDENTS").size(15).font(Font {
+        weight: font::Weight::Bold,
+        ..Font::default()
+    });
+
+    let list: Element<'a, ProcessWindowMessage> = if incidents.is_empty() {
+        text("No incidents recorded").size(14).into()
+    } else {
+        let entries = Column::with_children(incidents.iter().rev().map(|incident| {
+            // RFC 3339 timestamp, show date and time without the offset
+            let time = incident
+                .timestamp
+                .get(..19)
+                .unwrap_or(&incident.timestamp)
+                .replace('T', " ");
+            column![
+                text(format!(
+                    "{}  {:.0}°C (threshold {:.0}°C)  {:.2} GHz",
+                    time, incident.temperature, incident.threshold, incident.frequency
+                ))
+                .size(14),
+                text(incident.top_processes.replace(',', ", ")).size(13),
+            ]
+            .spacing(2)
+            .into()
+        }))
+        .spacing(10);
+        scrollable(entries)
+            .height(250)
+            .style(styles::thin_scrollbar_style)
+            .into()
+    };
+
+    container(
+        column![title, rule::horizontal(1), list]
+            .spacing(10)
+            .padding(10),
+    )
+    .width(Fill)
+    .style(styles::card_container_style)
+    .into()
+}
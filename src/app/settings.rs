@@ -1,5 +1,9 @@
 use crate::app::modal::modal;
 use crate::app::styles;
+use crate::app::tray_renderer::TrayIconStyle;
+use crate::utils::alert_engine::{default_alert_rules, AlertRule, TEMPERATURE_RULE_NAME};
+use crate::utils::csv_logger::{LogCompression, MetricGroup};
+use crate::utils::retention::RetentionPolicy;
 use crate::AppMessage;
 use anyhow::{Context, Result};
 use iced::widget::{
@@ -14,18 +18,77 @@ use std::{fmt, fs};
 #[derive(Serialize, Deserialize)]
 struct Config {
     theme: String,
+    #[serde(default = "default_tray_icon_style")]
+    tray_icon_style: TrayIconStyle,
     start_with_windows: bool,
     start_minimized: bool,
     selected_temp_units: TempUnits,
     data_update_interval: f32,
     temp_low_threshold: f32,
     temp_high_threshold: f32,
+    #[serde(default)]
+    battery_profile: BatteryProfile,
+    #[serde(default)]
+    log_processes: bool,
+    #[serde(default = "default_logged_metrics")]
+    logged_metrics: Vec<MetricGroup>,
+    #[serde(default)]
+    sqlite_storage: bool,
+    #[serde(default)]
+    retention: RetentionPolicy,
+    #[serde(default = "default_log_compression")]
+    log_compression: LogCompression,
+    #[serde(default = "default_true")]
+    alerts_enabled: bool,
+    #[serde(default = "default_true")]
+    notify_temp_alert: bool,
+    // Rules next to the high temperature alert, edited in the config file for now
+    #[serde(default = "default_alert_rules")]
+    alert_rules: Vec<AlertRule>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_tray_icon_style() -> TrayIconStyle {
+    TrayIconStyle::Digits
+}
+
+fn default_logged_metrics() -> Vec<MetricGroup> {
+    MetricGroup::ALL.to_vec()
+}
+
+fn default_log_compression() -> LogCompression {
+    LogCompression::None
+}
+
+/// Update interval and thresholds used instead of the normal ones while on battery
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BatteryProfile {
+    pub enabled: bool,
+    pub data_update_interval: f32,
+    pub temp_low_threshold: f32,
+    pub temp_high_threshold: f32,
+}
+
+impl Default for BatteryProfile {
+    fn default() -> Self {
+        // Poll less often to save power, laptops run hotter so thresholds are higher
+        Self {
+            enabled: false,
+            data_update_interval: 5.0,
+            temp_low_threshold: 50.0,
+            temp_high_threshold: 80.0,
+        }
+    }
 }
 
 // Runtime settings
 #[derive(Clone)]
 pub struct Settings {
     pub theme: Theme,
+    pub tray_icon_style: TrayIconStyle,
     pub start_with_windows: bool,
     pub start_minimized: bool,
     pub selected_temp_units: Option<TempUnits>,
@@ -35,6 +98,27 @@ pub struct Settings {
     pub temp_low_input: String,
     pub temp_high_input: String,
     pub update_interval_input: String,
+    pub battery_profile: BatteryProfile,
+    pub battery_temp_low_input: String,
+    pub battery_temp_high_input: String,
+    // Write the top processes to a separate CSV next to the CPU log
+    pub log_processes: bool,
+    // Column groups in the CPU log, changing them starts a new file
+    pub logged_metrics: Vec<MetricGroup>,
+    // Also store samples in logs/history.db for queries across days
+    pub sqlite_storage: bool,
+    // Roll up and delete old CPU logs in the background
+    pub retention: RetentionPolicy,
+    pub retention_raw_input: String,
+    pub retention_minute_input: String,
+    pub retention_delete_input: String,
+    // Finished daily CPU logs are compressed in the background with the log retention
+    pub log_compression: LogCompression,
+    // Alerts can be switched off from the tray menu too
+    pub alerts_enabled: bool,
+    // Desktop notification when the high temperature threshold is crossed
+    pub notify_temp_alert: bool,
+    pub alert_rules: Vec<AlertRule>,
 }
 #[derive(Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum TempUnits {
@@ -66,6 +150,7 @@ impl Default for Settings {
     fn default() -> Self {
         Self {
             theme: Theme::Dracula,
+            tray_icon_style: default_tray_icon_style(),
             start_with_windows: true,
             start_minimized: false,
             selected_temp_units: Some(TempUnits::Celsius),
@@ -75,6 +160,20 @@ impl Default for Settings {
             temp_low_input: "40".to_string(),
             temp_high_input: "70".to_string(),
             update_interval_input: "2.0".to_string(),
+            battery_profile: BatteryProfile::default(),
+            battery_temp_low_input: "50".to_string(),
+            battery_temp_high_input: "80".to_string(),
+            log_processes: false,
+            logged_metrics: default_logged_metrics(),
+            sqlite_storage: false,
+            retention: RetentionPolicy::default(),
+            retention_raw_input: "7".to_string(),
+            retention_minute_input: "30".to_string(),
+            retention_delete_input: "365".to_string(),
+            log_compression: default_log_compression(),
+            alerts_enabled: true,
+            notify_temp_alert: true,
+            alert_rules: default_alert_rules(),
         }
     }
 }
@@ -110,9 +209,15 @@ impl Settings {
 
         dbg!("Loaded config from disk");
 
+        // Invalid rules stay in the config so saving doesn't delete them
+        for rule in config.alert_rules.iter().filter(|rule| !rule.is_usable()) {
+            eprintln!("Skipping invalid alert rule: {}", rule.name);
+        }
+
         // Thresholds are stored in the selected unit, use them as-is for display
         Ok(Self {
             theme,
+            tray_icon_style: config.tray_icon_style,
             start_minimized: config.start_minimized,
             start_with_windows: config.start_with_windows,
             selected_temp_units: Some(config.selected_temp_units),
@@ -122,6 +227,20 @@ impl Settings {
             temp_low_input: format!("{:.0}", config.temp_low_threshold),
             temp_high_input: format!("{:.0}", config.temp_high_threshold),
             update_interval_input: config.data_update_interval.to_string(),
+            battery_profile: config.battery_profile,
+            battery_temp_low_input: format!("{:.0}", config.battery_profile.temp_low_threshold),
+            battery_temp_high_input: format!("{:.0}", config.battery_profile.temp_high_threshold),
+            log_processes: config.log_processes,
+            logged_metrics: config.logged_metrics,
+            sqlite_storage: config.sqlite_storage,
+            retention: config.retention,
+            retention_raw_input: config.retention.raw_days.to_string(),
+            retention_minute_input: config.retention.minute_days.to_string(),
+            retention_delete_input: config.retention.delete_after_days.to_string(),
+            log_compression: config.log_compression,
+            alerts_enabled: config.alerts_enabled,
+            notify_temp_alert: config.notify_temp_alert,
+            alert_rules: config.alert_rules,
         })
     }
 
@@ -136,6 +255,7 @@ impl Settings {
         let theme_name = self.theme.to_string();
         let config = Config {
             theme: theme_name,
+            tray_icon_style: self.tray_icon_style,
             start_minimized: self.start_minimized,
             start_with_windows: self.start_with_windows,
             selected_temp_units: self
@@ -144,6 +264,15 @@ impl Settings {
             data_update_interval: self.data_update_interval,
             temp_low_threshold: self.temp_low_threshold,
             temp_high_threshold: self.temp_high_threshold,
+            battery_profile: self.battery_profile,
+            log_processes: self.log_processes,
+            logged_metrics: self.logged_metrics.clone(),
+            sqlite_storage: self.sqlite_storage,
+            retention: self.retention,
+            log_compression: self.log_compression,
+            alerts_enabled: self.alerts_enabled,
+            notify_temp_alert: self.notify_temp_alert,
+            alert_rules: self.alert_rules.clone(),
         };
 
         let toml = toml::to_string_pretty(&config).context("Failed to serialize config")?;
@@ -153,6 +282,27 @@ impl Settings {
         Ok(())
     }
 
+    /// Update interval (seconds) for the current power source
+    pub fn active_update_interval(&self, on_battery: bool) -> f32 {
+        if on_battery && self.battery_profile.enabled {
+            self.battery_profile.data_update_interval
+        } else {
+            self.data_update_interval
+        }
+    }
+
+    /// (low, high) temperature thresholds for the current power source, in the selected unit
+    pub fn active_temp_thresholds(&self, on_battery: bool) -> (f32, f32) {
+        if on_battery && self.battery_profile.enabled {
+            (
+                self.battery_profile.temp_low_threshold,
+                self.battery_profile.temp_high_threshold,
+            )
+        } else {
+            (self.temp_low_threshold, self.temp_high_threshold)
+        }
+    }
+
     pub fn view<'a>(&'a self, base: Element<'a, AppMessage>) -> Element<'a, AppMessage> {
         // Header with title and close button
         let header = container(
@@ -189,6 +339,20 @@ impl Settings {
             )
             .width(Length::Fill)
             .padding(10),
+            text("Tray icon").size(15).style(|_theme| text::Style {
+                color: Some(Color::from_rgb(0.9, 0.9, 0.9))
+            }),
+            pick_list(
+                [
+                    TrayIconStyle::Digits,
+                    TrayIconStyle::Gauge,
+                    TrayIconStyle::Logo
+                ],
+                Some(self.tray_icon_style),
+                AppMessage::TrayIconStyleSelected,
+            )
+            .width(Length::Fill)
+            .padding(10),
         ]
         .spacing(8);
 
@@ -306,6 +470,202 @@ impl Settings {
         ]
         .spacing(8);
 
+        // ========== BATTERY SECTION ==========
+        let mut battery_section = iced::widget::column![
+            text("ON BATTERY").size(14).style(|_theme| text::Style {
+                color: Some(Color::from_rgb(0.6, 0.6, 0.6))
+            }),
+            checkbox(
+                "Use separate settings on battery",
+                self.battery_profile.enabled
+            )
+            .on_toggle(AppMessage::ToggleBatteryProfile),
+        ]
+        .spacing(8);
+        if self.battery_profile.enabled {
+            battery_section = battery_section.push(
+                column![
+                    text("Update Interval")
+                        .size(15)
+                        .style(|_theme| text::Style {
+                            color: Some(Color::from_rgb(0.9, 0.9, 0.9))
+                        }),
+                    row![
+                        slider(
+                            0.5..=10.0,
+                            self.battery_profile.data_update_interval,
+                            AppMessage::BatteryUpdateIntervalChanged
+                        )
+                        .step(0.5)
+                        .width(Length::Fill),
+                        container(
+                            text(format!("{:.1}s", self.battery_profile.data_update_interval))
+                                .size(14)
+                                .style(|_theme| text::Style {
+                                    color: Some(Color::from_rgb(0.8, 0.8, 0.8))
+                                })
+                        )
+                        .width(Length::Fixed(50.0))
+                        .align_x(iced::alignment::Horizontal::Right),
+                    ]
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+                    row![
+                        column![
+                            text(format!("Low ({})", unit.unwrap_or("°C")))
+                                .size(14)
+                                .style(|_theme| text::Style {
+                                    color: Some(Color::from_rgb(0.7, 0.7, 0.7))
+                                }),
+                            text_input("50", &self.battery_temp_low_input)
+                                .on_input(AppMessage::BatteryTempLowThresholdChanged)
+                                .padding(10)
+                                .width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(5),
+                        column![
+                            text(format!("High ({})", unit.unwrap_or("°C")))
+                                .size(14)
+                                .style(|_theme| text::Style {
+                                    color: Some(Color::from_rgb(0.7, 0.7, 0.7))
+                                }),
+                            text_input("80", &self.battery_temp_high_input)
+                                .on_input(AppMessage::BatteryTempHighThresholdChanged)
+                                .padding(10)
+                                .width(Length::Fixed(80.0)),
+                        ]
+                        .spacing(5),
+                    ]
+                    .spacing(15),
+                    text("Used instead of the values above while running from the battery")
+                        .size(12)
+                        .style(|_theme| text::Style {
+                            color: Some(Color::from_rgb(0.6, 0.6, 0.6))
+                        }),
+                ]
+                .spacing(5),
+            );
+        }
+
+        // ========== LOGGING SECTION ==========
+        let mut logging_section = iced::widget::column![
+            text("LOGGING").size(14).style(|_theme| text::Style {
+                color: Some(Color::from_rgb(0.6, 0.6, 0.6))
+            }),
+            checkbox("Log top processes to CSV", self.log_processes)
+                .on_toggle(AppMessage::ToggleProcessLogging),
+            checkbox("Store history in SQLite database", self.sqlite_storage)
+                .on_toggle(AppMessage::ToggleSqliteStorage),
+            text("CSV columns").size(15).style(|_theme| text::Style {
+                color: Some(Color::from_rgb(0.9, 0.9, 0.9))
+            }),
+        ]
+        .spacing(8);
+        // CPU columns are always logged
+        for group in &MetricGroup::ALL[1..] {
+            let group = *group;
+            logging_section = logging_section.push(
+                checkbox(group.to_string(), self.logged_metrics.contains(&group))
+                    .on_toggle(move |enabled| AppMessage::ToggleLoggedMetric(group, enabled)),
+            );
+        }
+        logging_section = logging_section.push(
+            text("Changing the columns continues today's log in a new file")
+                .size(12)
+                .style(|_theme| text::Style {
+                    color: Some(Color::from_rgb(0.6, 0.6, 0.6)),
+                }),
+        );
+        logging_section = logging_section.push(
+            row![
+                text("Compress finished logs")
+                    .size(15)
+                    .style(|_theme| text::Style {
+                        color: Some(Color::from_rgb(0.9, 0.9, 0.9))
+                    }),
+                pick_list(
+                    LogCompression::ALL,
+                    Some(self.log_compression),
+                    AppMessage::LogCompressionSelected,
+                )
+                .padding(10),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        );
+        logging_section = logging_section.push(
+            checkbox("Compact and delete old logs", self.retention.enabled)
+                .on_toggle(AppMessage::ToggleRetention),
+        );
+        if self.retention.enabled {
+            logging_section = logging_section.push(
+                column![
+                    row![
+                        days_input(
+                            "Raw (days)",
+                            &self.retention_raw_input,
+                            AppMessage::RetentionRawDaysChanged
+                        ),
+                        days_input(
+                            "1-minute (days)",
+                            &self.retention_minute_input,
+                            AppMessage::RetentionMinuteDaysChanged
+                        ),
+                        days_input(
+                            "Delete after (days)",
+                            &self.retention_delete_input,
+                            AppMessage::RetentionDeleteDaysChanged
+                        ),
+                    ]
+                    .spacing(20),
+                    text("Older logs are kept as 1-hour min/avg/max until deleted")
+                        .size(12)
+                        .style(|_theme| text::Style {
+                            color: Some(Color::from_rgb(0.6, 0.6, 0.6)),
+                        }),
+                ]
+                .spacing(5),
+            );
+        }
+
+        // ========== ALERTS SECTION ==========
+        let mut alerts_section = iced::widget::column![
+            text("ALERTS").size(14).style(|_theme| text::Style {
+                color: Some(Color::from_rgb(0.6, 0.6, 0.6))
+            }),
+            checkbox("Enable alerts", self.alerts_enabled).on_toggle(AppMessage::ToggleAlerts),
+            text("Desktop notifications")
+                .size(15)
+                .style(|_theme| text::Style {
+                    color: Some(Color::from_rgb(0.9, 0.9, 0.9))
+                }),
+            checkbox(TEMPERATURE_RULE_NAME, self.notify_temp_alert).on_toggle(|enabled| {
+                AppMessage::ToggleAlertNotification(TEMPERATURE_RULE_NAME.to_string(), enabled)
+            }),
+        ]
+        .spacing(8);
+        for rule in &self.alert_rules {
+            let label = if !rule.is_usable() {
+                format!("{} (invalid rule, ignored)", rule.name)
+            } else if rule.enabled {
+                rule.name.clone()
+            } else {
+                format!("{} (rule disabled)", rule.name)
+            };
+            let name = rule.name.clone();
+            alerts_section =
+                alerts_section.push(checkbox(label, rule.notify).on_toggle(move |enabled| {
+                    AppMessage::ToggleAlertNotification(name.clone(), enabled)
+                }));
+        }
+        alerts_section = alerts_section.push(
+            text("Shown when an alert fires. Rules are edited in the config file")
+                .size(12)
+                .style(|_theme| text::Style {
+                    color: Some(Color::from_rgb(0.6, 0.6, 0.6)),
+                }),
+        );
+
         // Save button
         let save_button = button(
             text("Save Settings")
@@ -349,6 +709,27 @@ impl Settings {
                                 fill_mode: rule::FillMode::Full,
                                 radius: 0.0.into(),
                             }),
+                            battery_section,
+                            rule::horizontal(1).style(move |_theme| rule::Style {
+                                color: separator_color,
+                                snap: false,
+                                fill_mode: rule::FillMode::Full,
+                                radius: 0.0.into(),
+                            }),
+                            logging_section,
+                            rule::horizontal(1).style(move |_theme| rule::Style {
+                                color: separator_color,
+                                snap: false,
+                                fill_mode: rule::FillMode::Full,
+                                radius: 0.0.into(),
+                            }),
+                            alerts_section,
+                            rule::horizontal(1).style(move |_theme| rule::Style {
+                                color: separator_color,
+                                snap: false,
+                                fill_mode: rule::FillMode::Full,
+                                radius: 0.0.into(),
+                            }),
                             save_button,
                         ]
                         .spacing(10)
@@ -380,3 +761,22 @@ impl Settings {
         )
     }
 }
+
+/// Labeled number of days input for the retention policy
+fn days_input<'a>(
+    label: &'a str,
+    value: &'a str,
+    on_input: fn(String) -> AppMessage,
+) -> Element<'a, AppMessage> {
+    column![
+        text(label).size(14).style(|_theme| text::Style {
+            color: Some(Color::from_rgb(0.7, 0.7, 0.7))
+        }),
+        text_input("", value)
+            .on_input(on_input)
+            .padding(10)
+            .width(Length::Fixed(80.0)),
+    ]
+    .spacing(5)
+    .into()
+}
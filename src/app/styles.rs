@@ -1,4 +1,5 @@
-use iced::widget::{button, container, scrollable};
+use crate::collectors::topology_collector::CoreType;
+use iced::widget::{button, container, progress_bar, scrollable};
 use iced::{Background, Color, Theme};
 
 /// Styling for components, currently only dark theme is supported
@@ -266,3 +267,29 @@ pub fn thin_scrollbar_style(_theme: &Theme, _status: scrollable::Status) -> scro
         gap: None,
     }
 }
+
+/// Per-core bar colored by core type. Second hardware threads of a core are dimmed
+pub fn core_bar_style(
+    core_type: CoreType,
+    smt_sibling: bool,
+) -> impl Fn(&Theme) -> progress_bar::Style {
+    move |_theme| {
+        let bar = match core_type {
+            CoreType::Performance => Color::from_rgb(0.35, 0.6, 0.95),
+            CoreType::Efficiency => Color::from_rgb(0.4, 0.8, 0.55),
+        };
+        progress_bar::Style {
+            background: Background::Color(Color::from_rgb(0.25, 0.25, 0.27)),
+            bar: Background::Color(if smt_sibling {
+                bar.scale_alpha(0.55)
+            } else {
+                bar
+            }),
+            border: iced::Border {
+                color: Color::TRANSPARENT,
+                width: 0.0,
+                radius: iced::border::Radius::from(4.0),
+            },
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use tray_icon::menu::{
+    CheckMenuItem, IsMenuItem, Menu, MenuId, MenuItem, PredefinedMenuItem, Submenu,
+};
+
+/// Update intervals (seconds) offered in the tray menu
+pub const UPDATE_INTERVALS: [f32; 5] = [0.5, 1.0, 2.0, 5.0, 10.0];
+
+/// What a tray menu click asks the app to do
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrayAction {
+    ShowWindow,
+    ShowPlots,
+    ToggleLogging,
+    OpenLogsFolder,
+    SetUpdateInterval(f32),
+    ToggleAlerts,
+    Quit,
+}
+
+/// Tray menu with live readouts at the top and quick actions below.
+/// Readouts are disabled items, they only display text
+pub struct TrayMenu {
+    temp_item: MenuItem,
+    usage_item: MenuItem,
+    power_item: MenuItem,
+    frequency_item: MenuItem,
+    logging_item: MenuItem,
+    alerts_item: CheckMenuItem,
+    interval_items: Vec<(f32, CheckMenuItem)>,
+    show_id: MenuId,
+    plots_id: MenuId,
+    open_logs_id: MenuId,
+    quit_id: MenuId,
+}
+
+impl TrayMenu {
+    /// Build the menu, the returned `Menu` goes to the tray icon
+    pub fn new(update_interval: f32, alerts_enabled: bool) -> (Self, Menu) {
+        let temp_item = MenuItem::new("Temperature: --", false, None);
+        let usage_item = MenuItem::new("Usage: --", false, None);
+        let power_item = MenuItem::new("Power: --", false, None);
+        let frequency_item = MenuItem::new("Frequency: --", false, None);
+
+        let show_item = MenuItem::new("Show Window", true, None);
+        let plots_item = MenuItem::new("Show Plots", true, None);
+        let logging_item = MenuItem::new("Pause Logging", true, None);
+        let open_logs_item = MenuItem::new("Open Logs Folder", true, None);
+        let alerts_item = CheckMenuItem::new("Alerts", true, alerts_enabled, None);
+        let quit_item = MenuItem::new("Quit", true, None);
+
+        let interval_items: Vec<(f32, CheckMenuItem)> = UPDATE_INTERVALS
+            .iter()
+            .map(|&interval| {
+                let item = CheckMenuItem::new(
+                    format!("{}s", interval),
+                    true,
+                    interval == update_interval,
+                    None,
+                );
+                (interval, item)
+            })
+            .collect();
+        let interval_refs: Vec<&dyn IsMenuItem> = interval_items
+            .iter()
+            .map(|(_, item)| item as &dyn IsMenuItem)
+            .collect();
+        let interval_menu = Submenu::with_items("Update Interval", true, &interval_refs)
+            .expect("Failed to create update interval menu");
+
+        let menu = Menu::new();
+        menu.append_items(&[
+            &temp_item,
+            &usage_item,
+            &power_item,
+            &frequency_item,
+            &PredefinedMenuItem::separator(),
+            &show_item,
+            &plots_item,
+            &PredefinedMenuItem::separator(),
+            &logging_item,
+            &open_logs_item,
+            &interval_menu,
+            &alerts_item,
+            &PredefinedMenuItem::separator(),
+            &quit_item,
+        ])
+        .expect("Failed to append menu items");
+
+        let tray_menu = Self {
+            temp_item,
+            usage_item,
+            power_item,
+            frequency_item,
+            logging_item,
+            alerts_item,
+            interval_items,
+            show_id: show_item.id().clone(),
+            plots_id: plots_item.id().clone(),
+            open_logs_id: open_logs_item.id().clone(),
+            quit_id: quit_item.id().clone(),
+        };
+        (tray_menu, menu)
+    }
+
+    pub fn action(&self, id: &MenuId) -> Option<TrayAction> {
+        if *id == self.show_id {
+            Some(TrayAction::ShowWindow)
+        } else if *id == self.plots_id {
+            Some(TrayAction::ShowPlots)
+        } else if id == self.logging_item.id() {
+            Some(TrayAction::ToggleLogging)
+        } else if *id == self.open_logs_id {
+            Some(TrayAction::OpenLogsFolder)
+        } else if id == self.alerts_item.id() {
+            Some(TrayAction::ToggleAlerts)
+        } else if *id == self.quit_id {
+            Some(TrayAction::Quit)
+        } else {
+            self.interval_items
+                .iter()
+                .find(|(_, item)| id == item.id())
+                .map(|(interval, _)| TrayAction::SetUpdateInterval(*interval))
+        }
+    }
+
+    pub fn set_readouts(&self, temp: &str, usage: &str, power: &str, frequency: &str) {
+        self.temp_item.set_text(format!("Temperature: {}", temp));
+        self.usage_item.set_text(format!("Usage: {}", usage));
+        self.power_item.set_text(format!("Power: {}", power));
+        self.frequency_item
+            .set_text(format!("Frequency: {}", frequency));
+    }
+
+    pub fn set_logging_paused(&self, paused: bool) {
+        self.logging_item.set_text(if paused {
+            "Resume Logging"
+        } else {
+            "Pause Logging"
+        });
+    }
+
+    // Check items toggle themselves when clicked, these put them back in sync with the app
+    pub fn set_alerts_enabled(&self, enabled: bool) {
+        self.alerts_item.set_checked(enabled);
+    }
+
+    pub fn set_update_interval(&self, update_interval: f32) {
+        for (interval, item) in &self.interval_items {
+            item.set_checked(*interval == update_interval);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Tray icons are drawn at this size, the OS scales them to fit
+pub const ICON_SIZE: u32 = 32;
+
+const BACKGROUND: Rgba<u8> = Rgba([32, 32, 36, 255]);
+const OUTLINE: Rgba<u8> = Rgba([170, 170, 175, 255]);
+const UNAVAILABLE: Rgba<u8> = Rgba([120, 120, 125, 255]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrayIconStyle {
+    Logo,
+    Digits,
+    Gauge,
+}
+
+impl fmt::Display for TrayIconStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrayIconStyle::Logo => write!(f, "Logo"),
+            TrayIconStyle::Digits => write!(f, "Temperature"),
+            TrayIconStyle::Gauge => write!(f, "Gauge"),
+        }
+    }
+}
+
+/// Where the temperature is relative to the thresholds in settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempLevel {
+    Low,    // At or below the low threshold
+    Medium, // Between the thresholds
+    High,   // At or above the high threshold
+}
+
+impl TempLevel {
+    pub fn new(temp: f32, low_threshold: f32, high_threshold: f32) -> Self {
+        if temp >= high_threshold {
+            TempLevel::High
+        } else if temp <= low_threshold {
+            TempLevel::Low
+        } else {
+            TempLevel::Medium
+        }
+    }
+
+    pub fn color(&self) -> Rgba<u8> {
+        match self {
+            TempLevel::Low => Rgba([80, 200, 120, 255]),
+            TempLevel::Medium => Rgba([240, 200, 60, 255]),
+            TempLevel::High => Rgba([235, 70, 70, 255]),
+        }
+    }
+}
+
+/// Render the tray icon for a temperature (`None` when unavailable or stale) and the
+/// low/high thresholds, all in the same unit. Returns `None` for the logo style
+pub fn render(
+    style: TrayIconStyle,
+    temp: Option<f32>,
+    low_threshold: f32,
+    high_threshold: f32,
+) -> Option<RgbaImage> {
+    let color = temp.map_or(UNAVAILABLE, |temp| {
+        TempLevel::new(temp, low_threshold, high_threshold).color()
+    });
+    match style {
+        TrayIconStyle::Logo => None,
+        TrayIconStyle::Digits => {
+            let label = temp.map_or("--".to_string(), |temp| format!("{:.0}", temp));
+            Some(render_digits(&label, color))
+        }
+        TrayIconStyle::Gauge => {
+            // Full at the high threshold
+            let fill = temp.map_or(0.0, |temp| temp / high_threshold);
+            Some(render_gauge(fill, color))
+        }
+    }
+}
+
+/// 3x5 pixel glyphs, one row per byte with the leftmost pixel in bit 2
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        _ => [0; 5],
+    }
+}
+
+/// Temperature digits, as large as fit, centered on a dark square
+fn render_digits(label: &str, color: Rgba<u8>) -> RgbaImage {
+    let mut image = RgbaImage::from_pixel(ICON_SIZE, ICON_SIZE, BACKGROUND);
+    let count = label.chars().count() as u32;
+    if count == 0 {
+        return image;
+    }
+    // Each glyph is 3 units wide with 1 unit between glyphs
+    let units = count * 4 - 1;
+    let scale = (ICON_SIZE / units).clamp(1, ICON_SIZE / 5);
+    let left = (ICON_SIZE - units * scale) / 2;
+    let top = (ICON_SIZE - 5 * scale) / 2;
+
+    for (i, c) in label.chars().enumerate() {
+        let x0 = left + i as u32 * 4 * scale;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                fill_rect(
+                    &mut image,
+                    x0 + col * scale,
+                    top + row as u32 * scale,
+                    scale,
+                    scale,
+                    color,
+                );
+            }
+        }
+    }
+    image
+}
+
+/// Vertical bar filled from the bottom, `fill` is clamped to 0..=1
+fn render_gauge(fill: f32, color: Rgba<u8>) -> RgbaImage {
+    let mut image = RgbaImage::new(ICON_SIZE, ICON_SIZE); // Transparent
+    let (x, width) = (8, ICON_SIZE - 16);
+    fill_rect(&mut image, x, 0, width, ICON_SIZE, OUTLINE);
+    fill_rect(&mut image, x + 2, 2, width - 4, ICON_SIZE - 4, BACKGROUND);
+
+    let inner = ICON_SIZE - 4;
+    let height = (fill.clamp(0.0, 1.0) * inner as f32).round() as u32;
+    fill_rect(
+        &mut image,
+        x + 2,
+        2 + inner - height,
+        width - 4,
+        height,
+        color,
+    );
+    image
+}
+
+fn fill_rect(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    for py in y..(y + height).min(image.height()) {
+        for px in x..(x + width).min(image.width()) {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utils::csv_logger::open_log;
 use anyhow::Result;
 use csv::ReaderBuilder;
 use iced::Color;
@@ -104,17 +105,24 @@ impl ChartSeries {
     }
 }
 
-/// Helper struct for deserializing CSV data
+/// Helper struct for deserializing CSV data. Columns are matched by header name, so
+/// any schema version works as long as the selected columns are in it
 #[derive(Debug, Deserialize)]
 struct CsvRecord {
     timestamp: String,
     temperature: Option<f32>,
     cpu_usage: Option<f32>,
     power_draw: Option<f32>,
+    // Missing in files written before network logging or with network logging disabled
+    #[serde(default)]
+    network_rx_rate: Option<f32>,
+    #[serde(default)]
+    network_tx_rate: Option<f32>,
 }
 
 /// Load chart series from a CSV file
-/// This function assumes your CSV format with semicolon delimiter
+/// This function assumes your CSV format with semicolon delimiter, the schema version
+/// line is skipped as a comment. Compressed daily archives are read as well
 pub fn load_series_from_csv(
     csv_path: impl AsRef<Path>,
     x_column: CsvColumn,
@@ -122,7 +130,10 @@ pub fn load_series_from_csv(
     label: impl Into<String>,
     color: Color,
 ) -> Result<ChartSeries> {
-    let mut reader = ReaderBuilder::new().delimiter(b';').from_path(csv_path)?;
+    let mut reader = ReaderBuilder::new()
+        .delimiter(b';')
+        .comment(Some(b'#'))
+        .from_reader(open_log(csv_path.as_ref())?);
 
     let mut series = ChartSeries::new(label, color);
 
@@ -139,6 +150,8 @@ pub fn load_series_from_csv(
             CsvColumn::Temperature => record.temperature.unwrap_or(0.0) as f64,
             CsvColumn::CpuUsage => record.cpu_usage.unwrap_or(0.0) as f64,
             CsvColumn::PowerDraw => record.power_draw.unwrap_or(0.0) as f64,
+            CsvColumn::NetworkRx => record.network_rx_rate.unwrap_or(0.0) as f64,
+            CsvColumn::NetworkTx => record.network_tx_rate.unwrap_or(0.0) as f64,
         };
 
         let y_value = match y_column {
@@ -147,6 +160,8 @@ pub fn load_series_from_csv(
             CsvColumn::Temperature => record.temperature.unwrap_or(0.0) as f64,
             CsvColumn::CpuUsage => record.cpu_usage.unwrap_or(0.0) as f64,
             CsvColumn::PowerDraw => record.power_draw.unwrap_or(0.0) as f64,
+            CsvColumn::NetworkRx => record.network_rx_rate.unwrap_or(0.0) as f64,
+            CsvColumn::NetworkTx => record.network_tx_rate.unwrap_or(0.0) as f64,
         };
 
         series.add_point(x_value, y_value);
@@ -163,4 +178,6 @@ pub enum CsvColumn {
     Temperature,
     CpuUsage,
     PowerDraw,
+    NetworkRx,
+    NetworkTx,
 }
@@ -0,0 +1,55 @@
+use super::{SensorKind, SensorSample};
+
+/// Readings for a single battery
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatteryData {
+    pub name: String,
+    pub charge_level: Option<f32>, // %
+    pub rate: Option<f32>,         // W, positive while charging, negative while discharging
+    pub wear_level: Option<f32>,   // % of design capacity lost
+    pub on_battery: bool,          // Running from the battery (AC unplugged)
+}
+
+impl BatteryData {
+    /// Build battery readings from the sensor list of one battery.
+    /// LHM reports either "Charge Rate" or "Discharge Rate", never both
+    pub fn from_sensors(name: impl Into<String>, sensors: &[SensorSample]) -> Self {
+        let find = |kind: SensorKind, wanted: &str| {
+            sensors
+                .iter()
+                .find(|s| s.kind == kind && s.name == wanted)
+                .map(|s| s.value)
+        };
+
+        let discharge_rate = find(SensorKind::Power, "Discharge Rate").filter(|w| *w > 0.0);
+        let rate = match discharge_rate {
+            Some(watts) => Some(-watts),
+            None => find(SensorKind::Power, "Charge Rate"),
+        };
+
+        Self {
+            name: name.into(),
+            charge_level: find(SensorKind::Level, "Charge Level"),
+            rate,
+            wear_level: find(SensorKind::Level, "Degradation Level"),
+            on_battery: discharge_rate.is_some(),
+        }
+    }
+
+    /// Wear from the full charge and design capacities (any unit, as long as both match)
+    pub fn wear_from_capacity(full: f32, design: f32) -> Option<f32> {
+        (design > 0.0).then(|| ((design - full) / design * 100.0).max(0.0))
+    }
+
+    /// "85% (-12.3 W)" style summary for the tray tooltip and collapsed card
+    pub fn summary(&self) -> String {
+        let level = self
+            .charge_level
+            .map(|level| format!("{:.0}%", level))
+            .unwrap_or_else(|| "--".to_string());
+        match self.rate {
+            Some(rate) => format!("{} ({:+.1} W)", level, rate),
+            None => level,
+        }
+    }
+}
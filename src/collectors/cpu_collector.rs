@@ -1,5 +1,5 @@
-use super::CoreStats;
-use crate::collectors::cpu_frequency_collector::FrequencyMonitor;
+use super::topology_collector::CpuTopology;
+use super::{CoreStats, PackageReading, SensorKind, SensorReadings, SensorSample};
 use sysinfo::System;
 
 pub struct CpuData {
@@ -7,6 +7,11 @@ pub struct CpuData {
     pub name: String,
     pub core_count: u32,
     pub base_cpu_frequency: f64,
+    // False until a backend reports the value (e.g. LHM service not connected)
+    pub temp_available: bool,
+    pub power_available: bool,
+    // Last sensor poll failed, temperature and power are the last good values
+    pub stale: bool,
     pub temp: f32,
     pub temp_low: f32,
     pub temp_high: f32,
@@ -16,16 +21,20 @@ pub struct CpuData {
     pub usage_high: f32,
     pub usage_avg: f32,
     pub core_utilization: Vec<CoreStats>,
+    pub core_frequencies: Vec<CoreStats>, // GHz, effective clock of each core
     pub total_power_draw: f32,
     pub core_power_draw: Vec<CoreStats>,
-    frequency_monitor: Option<FrequencyMonitor>,
+    pub core_temps: Vec<CoreStats>,
     pub current_frequency: f64,
+    // One entry per socket, empty if the backend doesn't report packages
+    pub packages: Vec<PackageReading>,
+    // Core types, SMT siblings and clusters. Empty until the platform reports it
+    pub topology: CpuTopology,
 }
 
 impl CpuData {
     pub fn new(sys: &System) -> Self {
         let base_freq = sys.cpus()[0].frequency() as f64 / 1000.0;
-        let frequency_monitor = FrequencyMonitor::new(base_freq).ok(); // If it fails just use base frequency
 
         let cores: Vec<CoreStats> = sys
             .cpus()
@@ -45,60 +54,137 @@ impl CpuData {
                 .to_string(),
             core_count: sys.cpus().len() as u32,
             base_cpu_frequency: base_freq,
+            temp_available: false,
+            power_available: false,
+            stale: false,
             temp: 0.0,
             temp_low: 0.0,
             temp_high: 0.0,
             total_power_draw: 0.0,
             core_power_draw: Vec::new(),
+            core_temps: Vec::new(),
             usage: sys.global_cpu_usage(),
             usage_low: sys.global_cpu_usage(),
             usage_high: sys.global_cpu_usage(),
             usage_avg: 0.0,
             core_utilization: cores,
-            frequency_monitor,
+            core_frequencies: Vec::new(),
             current_frequency: base_freq,
+            packages: Vec::new(),
+            topology: CpuTopology::default(),
             temp_avg: 0.0,
         }
     }
 
-    // lhm service updates
-    pub fn update_lhm_data(&mut self, temps: (f32, f32, Vec<CoreStats>)) {
-        if self.initial_run {
-            self.initial_run = false;
-            self.temp_low = temps.0;
-        }
-        self.temp = temps.0;
-        self.total_power_draw = temps.1;
-        self.core_power_draw = temps.2;
-        if self.temp < self.temp_low {
-            self.temp_low = self.temp;
-        }
-        if self.temp > self.temp_high {
-            self.temp_high = self.temp;
-        }
+    // Keep the last good values but flag them as outdated
+    pub fn mark_stale(&mut self) {
+        self.stale = true;
+    }
+
+    // The backend reporting temperature and power is gone, until a new one reports them
+    pub fn mark_unavailable(&mut self) {
+        self.temp_available = false;
+        self.power_available = false;
+        self.stale = false;
     }
 
-    // Method to update sysinfo and win32 api data
-    pub fn update(&mut self, sys: &mut System) {
-        sys.refresh_cpu_all();
-        let usage_update = sys.global_cpu_usage();
-        self.usage = usage_update;
-        if usage_update < self.usage_low {
-            self.usage_low = usage_update;
+    // Apply readings from one backend poll. Missing values keep their previous state
+    pub fn update(&mut self, readings: &SensorReadings) {
+        if let Some(temp) = readings.temp {
+            if self.initial_run {
+                self.initial_run = false;
+                self.temp_low = temp;
+            }
+            self.temp = temp;
+            self.temp_available = true;
+            self.stale = false;
+            if self.temp < self.temp_low {
+                self.temp_low = self.temp;
+            }
+            if self.temp > self.temp_high {
+                self.temp_high = self.temp;
+            }
+        }
+        if let Some(power) = readings.total_power_draw {
+            self.total_power_draw = power;
+            self.power_available = true;
+        }
+        if let Some(core_power) = &readings.core_power_draw {
+            self.core_power_draw = core_power.clone();
         }
-        if usage_update > self.usage_high {
-            self.usage_high = usage_update;
+        if let Some(core_temps) = &readings.core_temps {
+            self.core_temps = core_temps.clone();
         }
 
-        for (i, cpu) in sys.cpus().iter().enumerate() {
-            if let Some(core_data) = self.core_utilization.get_mut(i) {
-                core_data.value = cpu.cpu_usage();
+        if let Some(usage_update) = readings.usage {
+            self.usage = usage_update;
+            if usage_update < self.usage_low {
+                self.usage_low = usage_update;
+            }
+            if usage_update > self.usage_high {
+                self.usage_high = usage_update;
             }
         }
-        if let Some(ref monitor) = self.frequency_monitor {
-            if let Ok(freq) = monitor.get_current_frequency() {
-                self.current_frequency = freq;
+        if let Some(cores) = &readings.core_utilization {
+            for (i, core) in cores.iter().enumerate() {
+                if let Some(core_data) = self.core_utilization.get_mut(i) {
+                    core_data.value = core.value;
+                }
             }
         }
+        if let Some(freq) = readings.frequency {
+            self.current_frequency = freq;
+        }
+        if let Some(core_frequencies) = &readings.core_frequencies {
+            self.core_frequencies = core_frequencies.clone();
+        }
+        if let Some(packages) = &readings.packages {
+            self.packages = packages.clone();
+        }
     }
 }
+
+/// Per-core and per-CCD temperatures from a CPU's temperature sensors.
+/// Intel: "CPU Core #1" (LHM), "Core 0" (coretemp). AMD: "CCD1 (Tdie)" (LHM), "Tccd1" (k10temp).
+/// Package sensors, aggregates and distance to TjMax are skipped
+pub fn core_temps_from_sensors(sensors: &[SensorSample]) -> Vec<CoreStats> {
+    sensors
+        .iter()
+        .filter(|s| s.kind == SensorKind::Temperature)
+        .filter(|s| {
+            let name = s.name.as_str();
+            let skipped = ["Distance", "Max", "Average", "Tctl"]
+                .iter()
+                .any(|word| name.contains(word));
+            !skipped
+                && (name.starts_with("CPU Core #")
+                    || name.starts_with("Core ")
+                    || name.starts_with("CCD")
+                    || name.starts_with("Tccd"))
+        })
+        .map(|s| CoreStats {
+            name: s.name.clone(),
+            value: s.value,
+        })
+        .collect()
+}
+
+/// Per-core clocks (GHz) from a CPU's clock sensors (MHz). Newer LHM versions report an
+/// effective clock ("Core #1 (Effective)") next to the reported one, prefer it when present
+pub fn core_clocks_from_sensors(sensors: &[SensorSample]) -> Vec<CoreStats> {
+    let clocks: Vec<&SensorSample> = sensors
+        .iter()
+        .filter(|s| s.kind == SensorKind::Clock)
+        .filter(|s| s.name.starts_with("Core #") || s.name.starts_with("CPU Core #"))
+        .collect();
+    let has_effective = clocks.iter().any(|s| s.name.contains("Effective"));
+
+    clocks
+        .into_iter()
+        .filter(|s| s.name.contains("Effective") == has_effective)
+        .map(|s| CoreStats {
+            name: s.name.replace(" (Effective)", ""),
+            value: s.value / 1000.0,
+        })
+        .collect()
+}
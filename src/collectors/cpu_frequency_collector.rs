@@ -1,9 +1,14 @@
+use super::{CoreStats, ReadingsFuture, SensorBackend, SensorReadings};
 use windows::core::PCWSTR;
 use windows::Win32::System::Performance::{
-    PdhAddCounterW, PdhCloseQuery, PdhCollectQueryData, PdhGetFormattedCounterValue, PdhOpenQueryW,
-    PDH_FMT_COUNTERVALUE, PDH_FMT_DOUBLE,
+    PdhAddCounterW, PdhCloseQuery, PdhCollectQueryData, PdhGetFormattedCounterArrayW,
+    PdhGetFormattedCounterValue, PdhOpenQueryW, PDH_FMT_COUNTERVALUE, PDH_FMT_COUNTERVALUE_ITEM_W,
+    PDH_FMT_DOUBLE,
 };
 
+// Returned by PdhGetFormattedCounterArrayW when the buffer is too small
+const PDH_MORE_DATA: u32 = 0x800007D2;
+
 /*
 Keep freq monitoring as separate winapi query if user opts out of installing lhm-service
 */
@@ -11,6 +16,8 @@ Keep freq monitoring as separate winapi query if user opts out of installing lhm
 pub struct FrequencyMonitor {
     query: isize,
     counter: isize,
+    // Wildcard counter, one instance per logical processor ("0,0", "0,1", ...)
+    core_counter: isize,
     base_frequency: f64, // GHz
 }
 
@@ -33,12 +40,28 @@ impl FrequencyMonitor {
                 return Err(format!("PdhAddCounterW failed with error code: {}", result).into());
             }
 
+            let core_counter_path = "\\Processor Information(*)\\% Processor Performance\0"
+                .encode_utf16()
+                .collect::<Vec<u16>>();
+
+            let mut core_counter: isize = 0;
+            let result = PdhAddCounterW(
+                query,
+                PCWSTR(core_counter_path.as_ptr()),
+                0,
+                &mut core_counter,
+            );
+            if result != 0 {
+                return Err(format!("PdhAddCounterW failed with error code: {}", result).into());
+            }
+
             // Initial collect
             let _ = PdhCollectQueryData(query);
 
             Ok(Self {
                 query,
                 counter,
+                core_counter,
                 base_frequency: base_frequency_ghz,
             })
         }
@@ -73,6 +96,83 @@ impl FrequencyMonitor {
             Ok(current_freq)
         }
     }
+
+    /// Clock of every logical processor in GHz, from the last collected sample
+    pub fn get_core_frequencies(&self) -> Result<Vec<CoreStats>, Box<dyn std::error::Error>> {
+        unsafe {
+            // First call only reports the needed buffer size
+            let mut buffer_size: u32 = 0;
+            let mut item_count: u32 = 0;
+            let result = PdhGetFormattedCounterArrayW(
+                self.core_counter,
+                PDH_FMT_DOUBLE,
+                &mut buffer_size,
+                &mut item_count,
+                None,
+            );
+            if result != PDH_MORE_DATA {
+                return Err(format!(
+                    "PdhGetFormattedCounterArrayW failed with error code: {}",
+                    result
+                )
+                .into());
+            }
+
+            // Buffer holds the items followed by their instance name strings
+            let item_size = std::mem::size_of::<PDH_FMT_COUNTERVALUE_ITEM_W>();
+            let mut buffer: Vec<PDH_FMT_COUNTERVALUE_ITEM_W> =
+                Vec::with_capacity(buffer_size as usize / item_size + 1);
+            let result = PdhGetFormattedCounterArrayW(
+                self.core_counter,
+                PDH_FMT_DOUBLE,
+                &mut buffer_size,
+                &mut item_count,
+                Some(buffer.as_mut_ptr()),
+            );
+            if result != 0 {
+                return Err(format!(
+                    "PdhGetFormattedCounterArrayW failed with error code: {}",
+                    result
+                )
+                .into());
+            }
+            let items = std::slice::from_raw_parts(buffer.as_ptr(), item_count as usize);
+
+            // Instances are "group,number", skip the "_Total" aggregates
+            let mut cores: Vec<((u32, u32), f64)> = items
+                .iter()
+                .filter_map(|item| {
+                    let name = item.szName.to_string().ok()?;
+                    let (group, number) = name.split_once(',')?;
+                    let index = (group.parse().ok()?, number.parse().ok()?);
+                    Some((index, item.FmtValue.Anonymous.doubleValue))
+                })
+                .collect();
+            cores.sort_by_key(|(index, _)| *index);
+
+            Ok(cores
+                .into_iter()
+                .enumerate()
+                .map(|(i, (_, percent_performance))| CoreStats {
+                    name: format!("CPU {}", i),
+                    value: ((percent_performance / 100.0) * self.base_frequency) as f32,
+                })
+                .collect())
+        }
+    }
+}
+
+impl SensorBackend for FrequencyMonitor {
+    fn poll(&mut self) -> ReadingsFuture {
+        // get_current_frequency collects the query, per-core values come from the same sample
+        let frequency = self.get_current_frequency().ok();
+        let readings = SensorReadings {
+            frequency,
+            core_frequencies: frequency.and_then(|_| self.get_core_frequencies().ok()),
+            ..Default::default()
+        };
+        Box::pin(std::future::ready(Ok(readings)))
+    }
 }
 
 impl Drop for FrequencyMonitor {
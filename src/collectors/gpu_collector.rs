@@ -0,0 +1,81 @@
+use super::{SensorKind, SensorSample};
+
+/// Readings for a single GPU. Values the GPU or driver doesn't expose stay `None`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuData {
+    pub name: String,
+    pub core_temp: Option<f32>,
+    pub hotspot_temp: Option<f32>,
+    pub memory_temp: Option<f32>,
+    pub load: Option<f32>,
+    pub vram_used: Option<f32>,  // MB
+    pub vram_total: Option<f32>, // MB
+    pub power_draw: Option<f32>,
+    pub core_clock: Option<f32>,   // MHz
+    pub memory_clock: Option<f32>, // MHz
+}
+
+impl GpuData {
+    /// Build GPU readings from the sensor list of one GPU.
+    /// Sensor names follow LHM: "GPU Core", "GPU Hot Spot", "GPU Memory Junction",
+    /// "GPU Memory Used", "GPU Package"... with D3D fallbacks for integrated GPUs
+    pub fn from_sensors(name: impl Into<String>, sensors: &[SensorSample]) -> Self {
+        let find = |kind: SensorKind, names: &[&str]| {
+            names.iter().find_map(|wanted| {
+                sensors
+                    .iter()
+                    .find(|s| s.kind == kind && s.name == *wanted)
+                    .map(|s| s.value)
+            })
+        };
+        let find_containing = |kind: SensorKind, pattern: &str| {
+            sensors
+                .iter()
+                .find(|s| s.kind == kind && s.name.contains(pattern))
+                .map(|s| s.value)
+        };
+
+        let hotspot_temp = find_containing(SensorKind::Temperature, "Hot Spot")
+            .or_else(|| find_containing(SensorKind::Temperature, "Hotspot"));
+        let memory_temp = find_containing(SensorKind::Temperature, "Memory");
+        let core_temp =
+            find(SensorKind::Temperature, &["GPU Core", "GPU Temperature"]).or_else(|| {
+                // Anything that isn't hot spot or memory
+                sensors
+                    .iter()
+                    .find(|s| {
+                        s.kind == SensorKind::Temperature
+                            && !s.name.contains("Hot")
+                            && !s.name.contains("Memory")
+                    })
+                    .map(|s| s.value)
+            });
+
+        let vram_used = find(
+            SensorKind::SmallData,
+            &["GPU Memory Used", "D3D Dedicated Memory Used"],
+        );
+        let vram_free = find(SensorKind::SmallData, &["GPU Memory Free"]);
+        let vram_total = find(
+            SensorKind::SmallData,
+            &["GPU Memory Total", "D3D Dedicated Memory Total"],
+        )
+        .or_else(|| Some(vram_used? + vram_free?));
+
+        Self {
+            name: name.into(),
+            core_temp,
+            hotspot_temp,
+            memory_temp,
+            load: find(SensorKind::Load, &["GPU Core", "D3D 3D"]),
+            vram_used,
+            vram_total,
+            power_draw: find(
+                SensorKind::Power,
+                &["GPU Package", "GPU Power", "GPU PPT", "GPU Core"],
+            ),
+            core_clock: find(SensorKind::Clock, &["GPU Core"]),
+            memory_clock: find(SensorKind::Clock, &["GPU Memory"]),
+        }
+    }
+}
@@ -0,0 +1,439 @@
+use super::battery_collector::BatteryData;
+use super::cpu_collector::core_temps_from_sensors;
+use super::topology_collector::{CpuTopology, LogicalCpuInfo};
+use super::{
+    CoreStats, PackageReading, ReadingsFuture, SensorBackend, SensorError, SensorKind,
+    SensorReadings, SensorSample,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+pub const SYSFS_ROOT: &str = "/sys";
+
+// hwmon drivers that report CPU temperatures
+const CPU_TEMP_DRIVERS: [&str; 3] = ["coretemp", "k10temp", "zenpower"];
+
+/// RAPL zones that are read, by their "name" file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RaplZone {
+    Package(u64), // "package-N", the whole socket
+    Core,         // "core", all cores of the parent package
+}
+
+impl RaplZone {
+    fn new(name: &str) -> Option<Self> {
+        if let Some(id) = name.strip_prefix("package-") {
+            id.parse().ok().map(RaplZone::Package)
+        } else if name == "core" {
+            Some(RaplZone::Core)
+        } else {
+            None
+        }
+    }
+}
+
+/// Power of one RAPL package from the last two energy reads
+#[derive(Debug, Clone, Default)]
+struct RaplPower {
+    package: Option<f32>,
+    cores: Vec<CoreStats>,
+}
+
+/*
+Linux replacement for the LHM service. Reads CPU temperature from hwmon,
+package/core power from the RAPL powercap counters, clocks from cpufreq and
+laptop batteries from power_supply.
+*/
+pub struct HwmonBackend {
+    root: PathBuf,
+    // Previous RAPL energy counters (zone path -> microjoules) for power calculation
+    last_energy: Option<(Instant, Vec<(PathBuf, u64)>)>,
+}
+
+impl HwmonBackend {
+    /// `root` is the sysfs mount point, normally `SYSFS_ROOT`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            last_energy: None,
+        }
+    }
+
+    /// Core types from the hybrid PMU cpu lists, physical cores from the topology
+    /// ids. E-cores are clustered by their shared L2 (modules), other cores by the
+    /// shared L3 (CCDs on AMD). Doesn't change at runtime
+    pub fn read_topology(&self) -> CpuTopology {
+        let atom_cpus = read_trimmed(&self.root.join("devices/cpu_atom/cpus"))
+            .map(|list| parse_cpu_list(&list))
+            .unwrap_or_default();
+
+        let cpus = logical_cpus(&self.root)
+            .into_iter()
+            .filter_map(|cpu| {
+                let index: usize = file_name(&cpu)[3..].parse().ok()?;
+                let read_id = |file: &str| -> Option<u64> {
+                    read_trimmed(&cpu.join("topology").join(file))?.parse().ok()
+                };
+                let package = read_id("physical_package_id").unwrap_or(0);
+                let cache_id = |level: u64| -> Option<u64> {
+                    let cache = sorted_entries(&cpu.join("cache"))
+                        .into_iter()
+                        .find(|cache| {
+                            read_trimmed(&cache.join("level")) == Some(level.to_string())
+                        })?;
+                    Some((level << 24) | read_trimmed(&cache.join("id"))?.parse::<u64>().ok()?)
+                };
+                let efficiency = atom_cpus.contains(&index);
+                // sysfs cluster_id is the L2 module on Intel
+                let cluster = if efficiency {
+                    cache_id(2).or_else(|| read_id("cluster_id"))
+                } else {
+                    cache_id(3)
+                };
+                Some(LogicalCpuInfo {
+                    index,
+                    core_id: (package << 32) | read_id("core_id").unwrap_or(index as u64),
+                    cluster_id: (package << 32) | cluster.unwrap_or(0),
+                    efficiency_class: if efficiency { 0 } else { 1 },
+                })
+            })
+            .collect();
+        CpuTopology::from_logical(cpus)
+    }
+
+    /// Read all sensors. Power needs two reads to calculate, so it's `None` on the first call
+    pub fn read(&mut self, now: Instant) -> SensorReadings {
+        let mut errors = Vec::new();
+        let mut rapl = self.read_rapl_power(now, &mut errors);
+        // Package clock is the average of all cores
+        let core_frequencies = self.read_core_frequencies();
+        let frequency = (!core_frequencies.is_empty()).then(|| {
+            core_frequencies
+                .iter()
+                .map(|(_, c)| c.value as f64)
+                .sum::<f64>()
+                / core_frequencies.len() as f64
+        });
+
+        let packages = self.read_packages();
+        let mut readings = if packages.is_empty() {
+            // No temperature driver loaded, power and clocks are still there
+            let cores: Vec<CoreStats> = rapl.values_mut().flat_map(|p| p.cores.drain(..)).collect();
+            SensorReadings {
+                total_power_draw: rapl.values().filter_map(|p| p.package).reduce(|a, b| a + b),
+                core_power_draw: (!cores.is_empty()).then_some(cores),
+                core_frequencies: Some(core_frequencies.into_iter().map(|(_, c)| c).collect()),
+                ..Default::default()
+            }
+        } else {
+            let packages = packages
+                .into_iter()
+                .map(|(id, mut package)| {
+                    let power = rapl.remove(&id).unwrap_or_default();
+                    package.power_draw = power.package;
+                    package.core_power_draw = power.cores;
+                    package.core_frequencies = core_frequencies
+                        .iter()
+                        .filter(|(package_id, _)| *package_id == id)
+                        .map(|(_, core)| core.clone())
+                        .collect();
+                    package
+                })
+                .collect();
+            SensorReadings::from_packages(packages)
+        };
+        readings.frequency = frequency;
+        readings.errors = errors;
+        readings.batteries = Some(self.read_batteries());
+        readings
+    }
+
+    // One package per CPU temperature hwmon device, by its socket id. Intel coretemp has a
+    // device per socket with "Package id N", AMD k10temp one per node with "Tctl"/"Tdie".
+    // Without a package sensor the first input is the package temperature
+    fn read_packages(&self) -> Vec<(u64, PackageReading)> {
+        let cpu_hwmons = sorted_entries(&self.root.join("class/hwmon"))
+            .into_iter()
+            .filter(|hwmon| {
+                let driver = read_trimmed(&hwmon.join("name")).unwrap_or_default();
+                CPU_TEMP_DRIVERS.contains(&driver.as_str())
+            });
+
+        let mut packages = Vec::new();
+        for (i, hwmon) in cpu_hwmons.enumerate() {
+            let inputs = read_temp_inputs(&hwmon);
+            let Some((_, first)) = inputs.first() else {
+                continue;
+            };
+            let package_sensor = inputs.iter().find(|(label, _)| {
+                label.starts_with("Package") || label == "Tctl" || label == "Tdie"
+            });
+            let id = package_sensor
+                .and_then(|(label, _)| label.strip_prefix("Package id ")?.parse().ok())
+                .unwrap_or(i as u64);
+            let samples: Vec<SensorSample> = inputs
+                .iter()
+                .map(|(label, value)| SensorSample::new(label, SensorKind::Temperature, *value))
+                .collect();
+            packages.push((
+                id,
+                PackageReading {
+                    name: format!("CPU #{}", id),
+                    temp: package_sensor.map_or(*first, |(_, value)| *value),
+                    power_draw: None,
+                    core_power_draw: Vec::new(),
+                    core_temps: core_temps_from_sensors(&samples),
+                    core_frequencies: Vec::new(),
+                },
+            ));
+        }
+        packages.sort_by_key(|(id, _)| *id);
+        packages
+    }
+
+    // Power of every RAPL package by its id. Unreadable energy counters go to `errors`
+    fn read_rapl_power(
+        &mut self,
+        now: Instant,
+        errors: &mut Vec<SensorError>,
+    ) -> BTreeMap<u64, RaplPower> {
+        let powercap = self.root.join("class/powercap");
+        // Zones are "intel-rapl:N" and subzones "intel-rapl:N:M", what they measure is in
+        // their name. "psys" (whole platform) and "uncore"/"dram" subzones are skipped
+        let zones: Vec<(PathBuf, RaplZone)> = sorted_entries(&powercap)
+            .into_iter()
+            .filter(|path| file_name(path).starts_with("intel-rapl:"))
+            .filter_map(|path| {
+                let kind = RaplZone::new(&read_trimmed(&path.join("name"))?)?;
+                Some((path, kind))
+            })
+            .collect();
+        // Subzones belong to the package of their parent zone
+        let package_of = |zone: &Path, kind: RaplZone| match kind {
+            RaplZone::Package(id) => Some(id),
+            RaplZone::Core => {
+                let name = file_name(zone);
+                let (parent, _) = name.rsplit_once(':')?;
+                zones.iter().find_map(|(path, kind)| match kind {
+                    RaplZone::Package(id) if file_name(path) == parent => Some(*id),
+                    _ => None,
+                })
+            }
+        };
+
+        let mut energy: Vec<(PathBuf, u64)> = Vec::new();
+        let mut unreadable = None;
+        for (zone, _) in &zones {
+            match fs::read_to_string(zone.join("energy_uj")) {
+                Ok(uj) => energy.extend(uj.trim().parse().ok().map(|uj| (zone.clone(), uj))),
+                Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                    unreadable.get_or_insert(e);
+                }
+                Err(_) => {}
+            }
+        }
+        // Root only since Linux 5.10 (CVE-2020-8694), the power shows as unavailable
+        if let Some(e) = unreadable {
+            let reason = if e.kind() == io::ErrorKind::PermissionDenied {
+                "permission denied".to_string()
+            } else {
+                e.to_string()
+            };
+            errors.push(SensorError::ValueUnavailable(format!(
+                "RAPL energy_uj: {}",
+                reason
+            )));
+        }
+
+        let mut packages = BTreeMap::new();
+        let previous = self.last_energy.replace((now, energy.clone()));
+        let Some((last_time, last_energy)) = previous else {
+            return packages;
+        };
+        let elapsed = now.duration_since(last_time).as_secs_f64();
+        if elapsed <= 0.0 {
+            return packages;
+        }
+
+        for (zone, uj) in &energy {
+            let Some((_, last_uj)) = last_energy.iter().find(|(path, _)| path == zone) else {
+                continue;
+            };
+            // Counter wraps around at max_energy_range_uj
+            let delta = if uj >= last_uj {
+                uj - last_uj
+            } else {
+                let range: u64 = read_trimmed(&zone.join("max_energy_range_uj"))
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(*last_uj);
+                range - last_uj + uj
+            };
+            let watts = (delta as f64 / 1_000_000.0 / elapsed) as f32;
+
+            let Some(kind) = zones
+                .iter()
+                .find(|(path, _)| path == zone)
+                .map(|(_, kind)| *kind)
+            else {
+                continue;
+            };
+            let Some(id) = package_of(zone, kind) else {
+                continue;
+            };
+            let package: &mut RaplPower = packages.entry(id).or_default();
+            match kind {
+                RaplZone::Package(_) => package.package = Some(watts),
+                RaplZone::Core => package.cores.push(CoreStats {
+                    name: "core".to_string(),
+                    value: watts,
+                }),
+            }
+        }
+        packages
+    }
+
+    // Current clock of every logical CPU in GHz, with the socket it's on
+    fn read_core_frequencies(&self) -> Vec<(u64, CoreStats)> {
+        logical_cpus(&self.root)
+            .iter()
+            .filter_map(|cpu| {
+                let khz: f32 = read_trimmed(&cpu.join("cpufreq/scaling_cur_freq"))?
+                    .parse()
+                    .ok()?;
+                let package = read_trimmed(&cpu.join("topology/physical_package_id"))
+                    .and_then(|id| id.parse().ok())
+                    .unwrap_or(0);
+                Some((
+                    package,
+                    CoreStats {
+                        name: file_name(cpu),
+                        value: khz / 1_000_000.0,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    // Batteries report energy (µWh/µW) or charge (µAh/µA) depending on the driver
+    fn read_batteries(&self) -> Vec<BatteryData> {
+        let supplies = sorted_entries(&self.root.join("class/power_supply"));
+        let read_number = |path: &Path| -> Option<f32> { read_trimmed(path)?.parse().ok() };
+
+        // Desktops and some laptops have no "Mains" supply, fall back to the battery status
+        let ac_online = supplies
+            .iter()
+            .filter(|supply| read_trimmed(&supply.join("type")).as_deref() == Some("Mains"))
+            .filter_map(|supply| read_number(&supply.join("online")))
+            .reduce(f32::max)
+            .map(|online| online > 0.0);
+
+        let mut batteries = Vec::new();
+        for supply in &supplies {
+            if read_trimmed(&supply.join("type")).as_deref() != Some("Battery") {
+                continue;
+            }
+            let status = read_trimmed(&supply.join("status")).unwrap_or_default();
+            let discharging = status == "Discharging";
+
+            let power = read_number(&supply.join("power_now")).or_else(|| {
+                let current = read_number(&supply.join("current_now"))?;
+                let voltage = read_number(&supply.join("voltage_now"))?;
+                Some(current * voltage / 1_000_000.0)
+            });
+            let rate = power.map(|microwatts| {
+                let watts = microwatts / 1_000_000.0;
+                if discharging {
+                    -watts
+                } else {
+                    watts
+                }
+            });
+
+            let capacity = |full: &str, design: &str| {
+                let full = read_number(&supply.join(full))?;
+                let design = read_number(&supply.join(design))?;
+                BatteryData::wear_from_capacity(full, design)
+            };
+
+            batteries.push(BatteryData {
+                name: read_trimmed(&supply.join("model_name")).unwrap_or_else(|| file_name(supply)),
+                charge_level: read_number(&supply.join("capacity")),
+                rate,
+                wear_level: capacity("energy_full", "energy_full_design")
+                    .or_else(|| capacity("charge_full", "charge_full_design")),
+                on_battery: ac_online.map_or(discharging, |online| !online),
+            });
+        }
+        batteries
+    }
+}
+
+impl SensorBackend for HwmonBackend {
+    fn poll(&mut self) -> ReadingsFuture {
+        let readings = self.read(Instant::now());
+        Box::pin(std::future::ready(Ok(readings)))
+    }
+}
+
+/// `(label, °C)` for every `tempN_input` in a hwmon directory. Unlabeled inputs use the file name
+fn read_temp_inputs(hwmon: &Path) -> Vec<(String, f32)> {
+    sorted_entries(hwmon)
+        .iter()
+        .filter_map(|path| {
+            let name = file_name(path);
+            let prefix = name.strip_suffix("_input")?;
+            if !prefix.starts_with("temp") {
+                return None;
+            }
+            let millidegrees: f32 = read_trimmed(path)?.parse().ok()?;
+            let label = read_trimmed(&hwmon.join(format!("{}_label", prefix)))
+                .unwrap_or_else(|| prefix.to_string());
+            Some((label, millidegrees / 1000.0))
+        })
+        .collect()
+}
+
+/// "cpuN" directories, sorted by N
+fn logical_cpus(root: &Path) -> Vec<PathBuf> {
+    let mut cpus: Vec<(usize, PathBuf)> = sorted_entries(&root.join("devices/system/cpu"))
+        .into_iter()
+        .filter_map(|path| {
+            let index = file_name(&path).strip_prefix("cpu")?.parse().ok()?;
+            Some((index, path))
+        })
+        .collect();
+    cpus.sort_by_key(|(index, _)| *index);
+    cpus.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Kernel cpu list format: "0-3,8,10-11"
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((start, end)) => Some((start.parse().ok()?..=end.parse().ok()?).collect()),
+            None => Some(vec![range.parse().ok()?]),
+        })
+        .flatten()
+        .collect()
+}
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|rd| rd.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
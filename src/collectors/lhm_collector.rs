@@ -1,28 +1,313 @@
-use super::CoreStats;
-use lhm_client::{HardwareType, SensorType};
+use super::battery_collector::BatteryData;
+use super::cpu_collector::{core_clocks_from_sensors, core_temps_from_sensors};
+use super::gpu_collector::GpuData;
+use super::memory_collector::DimmReading;
+use super::motherboard_collector::MotherboardData;
+use super::psu_collector::PsuData;
+use super::storage_collector::StorageData;
+use super::{
+    CoreStats, PackageReading, ReadingsFuture, SensorBackend, SensorError, SensorKind,
+    SensorReadings, SensorSample,
+};
+use colored::Colorize;
+use lhm_client::{ComputerOptions, HardwareType, LHMClient, SensorType};
+use std::time::Duration;
 
-pub async fn lhm_cpu_queries(client: &lhm_client::LHMClientHandle) -> (f32, f32, Vec<CoreStats>) {
-    // Request all CPU hardware
-    let mut temp = 0.0;
-    let mut total_package_power = 0.0;
-    let mut core_power: Vec<CoreStats> = Vec::new();
+// Reconnect backoff while the service is missing or not running
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+async fn connect_to_lhwm_service() -> Option<lhm_client::LHMClientHandle> {
+    match LHMClient::connect().await {
+        Ok(client) => {
+            println!("Connected to hardware monitoring service");
+            let options = ComputerOptions {
+                controller_enabled: true,
+                cpu_enabled: true,
+                gpu_enabled: true,
+                motherboard_enabled: true,
+                battery_enabled: true,
+                memory_enabled: true,
+                network_enabled: false,
+                psu_enabled: true,
+                storage_enabled: true,
+            };
+            if let Err(e) = client.set_options(options).await {
+                eprintln!("{} {}", "Failed to set service options:".red(), e);
+                return None;
+            }
+            if let Err(e) = client.update_all().await {
+                eprintln!("{} {}", "Failed to update hardware:".red(), e);
+                return None;
+            }
+            println!("{}", "Service options set".green().bold());
+            Some(client)
+        }
+        Err(e) => {
+            eprintln!("{} {}", "Failed to connect to service: {}".red(), e);
+            eprintln!("{}", "The service may not be running. Try:".red());
+            eprintln!("{}", "1. Run 'install-service.bat' as administrator".red());
+            eprintln!(
+                "{}",
+                "2. Or manually start the service from Services (services.msc)".red()
+            );
+            None
+        }
+    }
+}
+
+/// Keep trying to connect with exponential backoff. Only returns once connected
+pub async fn connect_with_backoff() -> lhm_client::LHMClientHandle {
+    let mut delay = INITIAL_RETRY_DELAY;
+    loop {
+        if let Some(client) = connect_to_lhwm_service().await {
+            return client;
+        }
+        eprintln!("Retrying service connection in {}s", delay.as_secs());
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+}
+
+/// CPU temperature and power from the LibreHardwareMonitor service
+pub struct LhmBackend {
+    client: lhm_client::LHMClientHandle,
+}
+
+impl LhmBackend {
+    pub fn new(client: lhm_client::LHMClientHandle) -> Self {
+        Self { client }
+    }
+}
+
+impl SensorBackend for LhmBackend {
+    fn poll(&mut self) -> ReadingsFuture {
+        let client = self.client.clone();
+        Box::pin(async move {
+            client
+                .update_all()
+                .await
+                .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
+            // CPU readings are required, the other families are optional
+            let mut readings = SensorReadings::from_packages(lhm_cpu_queries(&client).await?);
+            readings.gpus = readings.family(lhm_gpu_queries(&client).await);
+            readings.drives = readings.family(lhm_storage_queries(&client).await);
+            readings.motherboard = readings.family(lhm_motherboard_queries(&client).await);
+            readings.dimm_temps = readings.family(lhm_memory_queries(&client).await);
+            readings.batteries = readings.family(lhm_battery_queries(&client).await);
+            readings.psus = readings.family(lhm_psu_queries(&client).await);
+            Ok(readings)
+        })
+    }
+}
+
+fn sensor_type(kind: SensorKind) -> SensorType {
+    match kind {
+        SensorKind::Temperature => SensorType::Temperature,
+        SensorKind::Load => SensorType::Load,
+        SensorKind::Power => SensorType::Power,
+        SensorKind::Clock => SensorType::Clock,
+        SensorKind::Fan => SensorType::Fan,
+        SensorKind::Control => SensorType::Control,
+        SensorKind::Voltage => SensorType::Voltage,
+        SensorKind::Level => SensorType::Level,
+        SensorKind::Data => SensorType::Data,
+        SensorKind::SmallData => SensorType::SmallData,
+    }
+}
+
+/// All sensors of the given kinds for one hardware item
+async fn query_samples(
+    client: &lhm_client::LHMClientHandle,
+    identifier: &str,
+    kinds: &[SensorKind],
+) -> Result<Vec<SensorSample>, SensorError> {
+    let mut samples = Vec::new();
+    for kind in kinds {
+        let sensors = client
+            .query_sensors(Some(identifier.to_string()), Some(sensor_type(*kind)))
+            .await
+            .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
+        samples.extend(
+            sensors
+                .into_iter()
+                .map(|sensor| SensorSample::new(sensor.name, *kind, sensor.value)),
+        );
+    }
+    Ok(samples)
+}
+
+/// Readings for every NVIDIA, AMD and Intel GPU
+pub async fn lhm_gpu_queries(
+    client: &lhm_client::LHMClientHandle,
+) -> Result<Vec<GpuData>, SensorError> {
+    let kinds = [
+        SensorKind::Temperature,
+        SensorKind::Load,
+        SensorKind::Power,
+        SensorKind::Clock,
+        SensorKind::SmallData,
+    ];
+
+    let mut gpus = Vec::new();
+    for hardware_type in [
+        HardwareType::GpuNvidia,
+        HardwareType::GpuAmd,
+        HardwareType::GpuIntel,
+    ] {
+        let gpu_list = client
+            .query_hardware(None, Some(hardware_type))
+            .await
+            .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
+        for gpu in gpu_list {
+            let samples = query_samples(client, &gpu.identifier, &kinds).await?;
+            gpus.push(GpuData::from_sensors(gpu.name, &samples));
+        }
+    }
+    Ok(gpus)
+}
+
+/// Temperature, used space and SMART wear for every drive
+pub async fn lhm_storage_queries(
+    client: &lhm_client::LHMClientHandle,
+) -> Result<Vec<StorageData>, SensorError> {
+    let kinds = [
+        SensorKind::Temperature,
+        SensorKind::Load,
+        SensorKind::Level,
+        SensorKind::Data,
+    ];
+
+    let drive_list = client
+        .query_hardware(None, Some(HardwareType::Storage))
+        .await
+        .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
+
+    let mut drives = Vec::with_capacity(drive_list.len());
+    for drive in drive_list {
+        let samples = query_samples(client, &drive.identifier, &kinds).await?;
+        drives.push(StorageData::from_sensors(drive.name, &samples));
+    }
+    Ok(drives)
+}
 
+/// Fans and voltages from the Super I/O chip(s) and standalone fan controllers.
+/// Super I/O chips are sub-hardware of the motherboard, coolers are top level
+pub async fn lhm_motherboard_queries(
+    client: &lhm_client::LHMClientHandle,
+) -> Result<MotherboardData, SensorError> {
+    let kinds = [SensorKind::Fan, SensorKind::Control, SensorKind::Voltage];
+
+    let boards = client
+        .query_hardware(None, Some(HardwareType::Motherboard))
+        .await
+        .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
+    let mut chips = Vec::new();
+    for board in boards {
+        chips.extend(
+            client
+                .query_hardware(Some(board.identifier), Some(HardwareType::SuperIO))
+                .await
+                .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?,
+        );
+    }
+    chips.extend(
+        client
+            .query_hardware(None, Some(HardwareType::Cooler))
+            .await
+            .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?,
+    );
+
+    let mut motherboard = MotherboardData::default();
+    for chip in chips {
+        let samples = query_samples(client, &chip.identifier, &kinds).await?;
+        motherboard.extend(MotherboardData::from_sensors(&samples));
+    }
+    Ok(motherboard)
+}
+
+/// Temperature of every memory module that has a sensor (DDR5, some DDR4)
+pub async fn lhm_memory_queries(
+    client: &lhm_client::LHMClientHandle,
+) -> Result<Vec<DimmReading>, SensorError> {
+    let modules = client
+        .query_hardware(None, Some(HardwareType::Memory))
+        .await
+        .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
+
+    let mut dimms = Vec::new();
+    for module in modules {
+        let samples = query_samples(client, &module.identifier, &[SensorKind::Temperature]).await?;
+        dimms.extend(DimmReading::from_sensors(module.name, &samples));
+    }
+    Ok(dimms)
+}
+
+/// Charge level, charge/discharge rate and wear for every laptop battery
+pub async fn lhm_battery_queries(
+    client: &lhm_client::LHMClientHandle,
+) -> Result<Vec<BatteryData>, SensorError> {
+    let kinds = [SensorKind::Level, SensorKind::Power];
+
+    let battery_list = client
+        .query_hardware(None, Some(HardwareType::Battery))
+        .await
+        .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
+
+    let mut batteries = Vec::with_capacity(battery_list.len());
+    for battery in battery_list {
+        let samples = query_samples(client, &battery.identifier, &kinds).await?;
+        batteries.push(BatteryData::from_sensors(battery.name, &samples));
+    }
+    Ok(batteries)
+}
+
+/// Power, efficiency, fan and temperature for every smart PSU
+pub async fn lhm_psu_queries(
+    client: &lhm_client::LHMClientHandle,
+) -> Result<Vec<PsuData>, SensorError> {
+    let kinds = [
+        SensorKind::Power,
+        SensorKind::Level,
+        SensorKind::Fan,
+        SensorKind::Temperature,
+    ];
+
+    let psu_list = client
+        .query_hardware(None, Some(HardwareType::Psu))
+        .await
+        .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
+
+    let mut psus = Vec::with_capacity(psu_list.len());
+    for psu in psu_list {
+        let samples = query_samples(client, &psu.identifier, &kinds).await?;
+        psus.push(PsuData::from_sensors(psu.name, &samples));
+    }
+    Ok(psus)
+}
+
+/// Temperature, power (if the CPU reports it) and per-core power for every CPU package
+pub async fn lhm_cpu_queries(
+    client: &lhm_client::LHMClientHandle,
+) -> Result<Vec<PackageReading>, SensorError> {
+    // Request all CPU hardware
     let cpu_list = client
         .query_hardware(None, Some(HardwareType::Cpu))
         .await
-        .unwrap();
+        .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
 
-    for cpu in cpu_list {
+    let mut packages = Vec::with_capacity(cpu_list.len());
+    for (i, cpu) in cpu_list.iter().enumerate() {
         // Request all CPU temperature sensors
         let total_temp_query = client
             .query_sensors(Some(cpu.identifier.clone()), Some(SensorType::Temperature))
             .await
-            .unwrap();
+            .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
 
         let power_query = client
             .query_sensors(Some(cpu.identifier.clone()), Some(SensorType::Power))
             .await
-            .unwrap();
+            .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?;
 
         // Find the CPU temperature sensor
         // "CPU Package" (Intel), "Core (Tctl/Tdie)" (AMD), "CPU Core" (generic)
@@ -35,15 +320,25 @@ pub async fn lhm_cpu_queries(client: &lhm_client::LHMClientHandle) -> (f32, f32,
                     || sensor.name.contains("Package")
                     || sensor.name.contains("Tctl")
             })
-            .expect("Missing cpu temp sensor");
+            .ok_or_else(|| SensorError::SensorNotFound("CPU package temperature".to_string()))?;
 
-        let total = power_query
+        // Not every CPU exposes package power, keep going without it
+        let power_draw = power_query
             .iter()
             .find(|sensor| sensor.name.contains("Package"))
-            .unwrap();
-        total_package_power = total.value;
+            .map(|sensor| sensor.value);
+
+        let temp_samples: Vec<SensorSample> = total_temp_query
+            .iter()
+            .map(|sensor| {
+                SensorSample::new(sensor.name.clone(), SensorKind::Temperature, sensor.value)
+            })
+            .collect();
+        let core_temps = core_temps_from_sensors(&temp_samples);
+        let clock_samples = query_samples(client, &cpu.identifier, &[SensorKind::Clock]).await?;
+        let core_frequencies = core_clocks_from_sensors(&clock_samples);
 
-        core_power = power_query
+        let core_power_draw = power_query
             .iter()
             .filter(|sensor| sensor.name.contains("Core"))
             .map(|sensor| CoreStats {
@@ -53,11 +348,24 @@ pub async fn lhm_cpu_queries(client: &lhm_client::LHMClientHandle) -> (f32, f32,
             .collect();
 
         // Get the current sensor value
-        temp = client
+        let temp = client
             .get_sensor_value_by_idx(temp_sensor.index, true)
             .await
-            .unwrap()
-            .expect("cpu temp sensor is now unavailable");
+            .map_err(|e| SensorError::ServiceDisconnected(e.to_string()))?
+            .ok_or_else(|| SensorError::ValueUnavailable(temp_sensor.name.clone()))?;
+
+        packages.push(PackageReading {
+            name: format!("CPU #{}", i),
+            temp,
+            power_draw,
+            core_power_draw,
+            core_temps,
+            core_frequencies,
+        });
+    }
+
+    if packages.is_empty() {
+        return Err(SensorError::SensorNotFound("CPU".to_string()));
     }
-    (temp, total_package_power, core_power)
+    Ok(packages)
 }
@@ -0,0 +1,115 @@
+use super::process_collector::{top_n, BYTES_PER_MB, TOP_PROCESS_COUNT};
+use super::{SensorKind, SensorReadings, SensorSample};
+use std::collections::BTreeMap;
+use sysinfo::System;
+
+const BYTES_PER_GB: f32 = 1024.0 * 1024.0 * 1024.0;
+
+/// Memory used by all processes sharing a name (browsers run dozens of them)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessMemory {
+    pub name: String,
+    pub memory: f32, // MB
+}
+
+/// Temperature of a single memory module, from its SPD hub sensor
+#[derive(Debug, Clone, PartialEq)]
+pub struct DimmReading {
+    pub name: String,
+    pub temp: f32,
+}
+
+impl DimmReading {
+    /// Module temperature from the sensor list of one DIMM, `None` if it has no sensor
+    pub fn from_sensors(name: impl Into<String>, sensors: &[SensorSample]) -> Option<Self> {
+        sensors
+            .iter()
+            .find(|s| s.kind == SensorKind::Temperature)
+            .map(|s| Self {
+                name: name.into(),
+                temp: s.value,
+            })
+    }
+}
+
+/// RAM and swap usage (GB), top memory consumers and DIMM temperatures.
+/// Usage comes from sysinfo, DIMM temperatures from LHM
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryData {
+    pub total: f32,
+    pub used: f32,
+    pub available: f32,
+    pub swap_total: f32,
+    pub swap_used: f32,
+    pub top_processes: Vec<ProcessMemory>,
+    pub dimm_temps: Vec<DimmReading>,
+}
+
+impl MemoryData {
+    /// Refresh memory usage and read it from the system. Processes need to be refreshed
+    /// (with memory) by the caller
+    pub fn from_system(system: &mut System) -> Self {
+        system.refresh_memory();
+
+        let processes = system.processes().values().map(|process| {
+            (
+                process.name().to_string_lossy().into_owned(),
+                process.memory(),
+            )
+        });
+
+        Self {
+            total: system.total_memory() as f32 / BYTES_PER_GB,
+            used: system.used_memory() as f32 / BYTES_PER_GB,
+            available: system.available_memory() as f32 / BYTES_PER_GB,
+            swap_total: system.total_swap() as f32 / BYTES_PER_GB,
+            swap_used: system.used_swap() as f32 / BYTES_PER_GB,
+            top_processes: top_consumers(processes, TOP_PROCESS_COUNT),
+            dimm_temps: Vec::new(),
+        }
+    }
+
+    /// Apply the memory values of a backend poll. Usage and DIMM temperatures come
+    /// from different backends, so each only replaces its own part
+    pub fn update(&mut self, readings: &SensorReadings) {
+        if let Some(memory) = &readings.memory {
+            let dimm_temps = std::mem::take(&mut self.dimm_temps);
+            *self = Self {
+                dimm_temps,
+                ..memory.clone()
+            };
+        }
+        if let Some(dimm_temps) = &readings.dimm_temps {
+            self.dimm_temps = dimm_temps.clone();
+        }
+    }
+
+    pub fn usage_percent(&self) -> f32 {
+        if self.total > 0.0 {
+            self.used / self.total * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Sum memory (bytes) per process name and return the `count` largest, biggest first
+pub fn top_consumers(
+    processes: impl IntoIterator<Item = (String, u64)>,
+    count: usize,
+) -> Vec<ProcessMemory> {
+    // Sorted by name, so equal sizes stay in name order
+    let mut by_name: BTreeMap<String, u64> = BTreeMap::new();
+    for (name, memory) in processes {
+        *by_name.entry(name).or_default() += memory;
+    }
+
+    let grouped = by_name
+        .into_iter()
+        .map(|(name, memory)| ProcessMemory {
+            name,
+            memory: memory as f32 / BYTES_PER_MB,
+        })
+        .collect();
+    top_n(grouped, count, |process| process.memory)
+}
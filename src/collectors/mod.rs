@@ -1,6 +1,32 @@
+pub mod battery_collector;
 pub mod cpu_collector;
+#[cfg(windows)]
 pub mod cpu_frequency_collector;
+pub mod gpu_collector;
+#[cfg(target_os = "linux")]
+pub mod hwmon_collector;
+#[cfg(windows)]
 pub mod lhm_collector;
+pub mod memory_collector;
+pub mod motherboard_collector;
+pub mod network_collector;
+pub mod process_collector;
+pub mod psu_collector;
+pub mod storage_collector;
+pub mod sysinfo_collector;
+pub mod topology_collector;
+
+use battery_collector::BatteryData;
+use gpu_collector::GpuData;
+use memory_collector::{DimmReading, MemoryData};
+use motherboard_collector::MotherboardData;
+use network_collector::InterfaceData;
+use process_collector::ProcessData;
+use psu_collector::PsuData;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use storage_collector::StorageData;
 
 /// Shared data structure for CPU core statistics (usage, power, etc.)
 #[derive(Debug, Clone)]
@@ -8,3 +34,157 @@ pub struct CoreStats {
     pub name: String,
     pub value: f32,
 }
+
+/// Kind of a raw sensor value, mirrors the sensor types LHM reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorKind {
+    Temperature,
+    Load,
+    Power,
+    Clock,
+    Fan,       // RPM
+    Control,   // %
+    Voltage,   // V
+    Level,     // %
+    Data,      // GB
+    SmallData, // MB
+}
+
+/// A raw sensor value as reported by the hardware monitor. Collectors parse lists of
+/// these so the parsing can be tested against recorded sensor lists without the service
+#[derive(Debug, Clone)]
+pub struct SensorSample {
+    pub name: String,
+    pub kind: SensorKind,
+    pub value: f32,
+}
+
+impl SensorSample {
+    pub fn new(name: impl Into<String>, kind: SensorKind, value: f32) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            value,
+        }
+    }
+}
+
+/// Readings for one physical CPU package (socket)
+#[derive(Debug, Clone)]
+pub struct PackageReading {
+    pub name: String,
+    pub temp: f32,
+    pub power_draw: Option<f32>,
+    pub core_power_draw: Vec<CoreStats>,
+    pub core_temps: Vec<CoreStats>,
+    pub core_frequencies: Vec<CoreStats>,
+}
+
+/// Values reported by a single backend poll.
+/// Fields a backend can't provide are left as `None` so other backends can fill them.
+#[derive(Debug, Clone, Default)]
+pub struct SensorReadings {
+    pub temp: Option<f32>,
+    pub usage: Option<f32>,
+    pub core_utilization: Option<Vec<CoreStats>>,
+    pub total_power_draw: Option<f32>,
+    pub core_power_draw: Option<Vec<CoreStats>>,
+    // Per-core or per-CCD, whichever the CPU reports
+    pub core_temps: Option<Vec<CoreStats>>,
+    pub frequency: Option<f64>,                   // GHz
+    pub core_frequencies: Option<Vec<CoreStats>>, // GHz
+    pub packages: Option<Vec<PackageReading>>,
+    pub gpus: Option<Vec<GpuData>>,
+    pub drives: Option<Vec<StorageData>>,
+    pub motherboard: Option<MotherboardData>,
+    pub memory: Option<MemoryData>,
+    pub dimm_temps: Option<Vec<DimmReading>>,
+    pub network: Option<Vec<InterfaceData>>,
+    pub batteries: Option<Vec<BatteryData>>,
+    pub psus: Option<Vec<PsuData>>,
+    pub processes: Option<Vec<ProcessData>>,
+    // Families that failed while the rest of the poll succeeded
+    pub errors: Vec<SensorError>,
+}
+
+impl SensorReadings {
+    /// Aggregate per-socket readings: hottest package temperature, summed power and
+    /// all cores. Core names get a socket prefix when there's more than one package
+    pub fn from_packages(packages: Vec<PackageReading>) -> Self {
+        let multi_socket = packages.len() > 1;
+        let temp = packages.iter().map(|p| p.temp).reduce(f32::max);
+        let total_power_draw = packages
+            .iter()
+            .filter_map(|p| p.power_draw)
+            .reduce(|a, b| a + b);
+        let all_cores = |cores: fn(&PackageReading) -> &Vec<CoreStats>| -> Vec<CoreStats> {
+            packages
+                .iter()
+                .enumerate()
+                .flat_map(|(i, package)| {
+                    cores(package).iter().map(move |core| CoreStats {
+                        name: if multi_socket {
+                            format!("S{} {}", i, core.name)
+                        } else {
+                            core.name.clone()
+                        },
+                        value: core.value,
+                    })
+                })
+                .collect()
+        };
+        let core_power_draw = all_cores(|p| &p.core_power_draw);
+        let core_temps = all_cores(|p| &p.core_temps);
+        let core_frequencies = all_cores(|p| &p.core_frequencies);
+
+        Self {
+            temp,
+            total_power_draw,
+            // Don't replace per-core clocks from PDH/cpufreq when LHM has no clock sensors
+            core_power_draw: Some(core_power_draw),
+            core_temps: Some(core_temps),
+            core_frequencies: (!core_frequencies.is_empty()).then_some(core_frequencies),
+            packages: Some(packages),
+            ..Default::default()
+        }
+    }
+
+    /// Value of one sensor family, a failed family is left as `None` and its error
+    /// kept in `errors` so it doesn't discard the families that were read
+    pub fn family<T>(&mut self, result: Result<T, SensorError>) -> Option<T> {
+        result.map_err(|e| self.errors.push(e)).ok()
+    }
+}
+
+/// Why a backend poll produced no readings
+#[derive(Debug, Clone, PartialEq)]
+pub enum SensorError {
+    /// Hardware monitor service didn't answer (stopped, crashed, pipe closed)
+    ServiceDisconnected(String),
+    /// No sensor matching the expected names
+    SensorNotFound(String),
+    /// Sensor exists but has no current value
+    ValueUnavailable(String),
+}
+
+impl fmt::Display for SensorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SensorError::ServiceDisconnected(e) => write!(f, "Service disconnected: {}", e),
+            SensorError::SensorNotFound(name) => write!(f, "Sensor not found: {}", name),
+            SensorError::ValueUnavailable(name) => write!(f, "Sensor value unavailable: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for SensorError {}
+
+pub type ReadingsFuture = Pin<Box<dyn Future<Output = Result<SensorReadings, SensorError>> + Send>>;
+
+/// A source of hardware sensor data (LHM service, Win32 PDH, Linux sysfs, sysinfo, ...)
+///
+/// Synchronous backends do their work in `poll` and return a ready future.
+/// Async backends clone what they need into the returned future.
+pub trait SensorBackend {
+    fn poll(&mut self) -> ReadingsFuture;
+}
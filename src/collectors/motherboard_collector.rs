@@ -0,0 +1,121 @@
+use super::{SensorKind, SensorSample};
+
+// ATX allows ±5% on the main rails
+pub const RAIL_TOLERANCE: f32 = 5.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanReading {
+    pub name: String,
+    pub rpm: f32,
+    pub control: Option<f32>, // PWM/DC duty in %
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoltageReading {
+    pub name: String,
+    pub volts: f32,
+    pub nominal: Option<f32>, // Expected rail voltage, None for Vcore and unknown rails
+}
+
+impl VoltageReading {
+    /// Deviation from the nominal rail voltage in %
+    pub fn deviation(&self) -> Option<f32> {
+        self.nominal
+            .map(|nominal| (self.volts - nominal) / nominal * 100.0)
+    }
+
+    /// Rail is outside the ATX tolerance
+    pub fn out_of_spec(&self) -> bool {
+        self.deviation()
+            .is_some_and(|deviation| deviation.abs() > RAIL_TOLERANCE)
+    }
+}
+
+/// Fans and voltages from the motherboard Super I/O chip and fan controllers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MotherboardData {
+    pub fans: Vec<FanReading>,
+    pub voltages: Vec<VoltageReading>,
+}
+
+impl MotherboardData {
+    /// Build readings from the sensor list of one chip. Fan controls share the fan's name
+    pub fn from_sensors(sensors: &[SensorSample]) -> Self {
+        let fans = sensors
+            .iter()
+            .filter(|s| s.kind == SensorKind::Fan)
+            .map(|fan| FanReading {
+                name: fan.name.clone(),
+                rpm: fan.value,
+                control: sensors
+                    .iter()
+                    .find(|s| s.kind == SensorKind::Control && s.name == fan.name)
+                    .map(|s| s.value),
+            })
+            .collect();
+
+        let voltages = sensors
+            .iter()
+            .filter(|s| s.kind == SensorKind::Voltage)
+            .map(|rail| VoltageReading {
+                name: rail.name.clone(),
+                volts: rail.value,
+                nominal: nominal_voltage(&rail.name),
+            })
+            .collect();
+
+        Self { fans, voltages }
+    }
+
+    /// Append readings from another chip (e.g. a fan controller next to the Super I/O)
+    pub fn extend(&mut self, other: MotherboardData) {
+        self.fans.extend(other.fans);
+        self.voltages.extend(other.voltages);
+    }
+
+    /// Fans with a duty above zero, headers turned off on purpose are skipped
+    fn driven_fans(&self) -> impl Iterator<Item = &FanReading> {
+        self.fans
+            .iter()
+            .filter(|fan| fan.control.is_some_and(|duty| duty > 0.0))
+    }
+
+    /// Lowest RPM of the driven fans, for the fan alert
+    pub fn slowest_fan_rpm(&self) -> Option<f32> {
+        self.driven_fans().map(|fan| fan.rpm).reduce(f32::min)
+    }
+
+    /// Largest deviation from nominal of the known rails in %, for the rail alert
+    pub fn worst_rail_deviation(&self) -> Option<f32> {
+        self.voltages
+            .iter()
+            .filter_map(|rail| rail.deviation())
+            .map(f32::abs)
+            .reduce(f32::max)
+    }
+
+    /// Stopped fans that are being driven and rails outside the ATX tolerance
+    pub fn warnings(&self) -> Vec<String> {
+        let stalled = self
+            .driven_fans()
+            .filter(|fan| fan.rpm < 1.0)
+            .map(|fan| format!("{} stopped", fan.name));
+        let out_of_spec = self
+            .voltages
+            .iter()
+            .filter(|rail| rail.out_of_spec())
+            .map(|rail| format!("{} at {:.2} V", rail.name, rail.volts));
+        stalled.chain(out_of_spec).collect()
+    }
+}
+
+// "+12V", "12V", "+12 V" -> 12.0
+fn nominal_voltage(name: &str) -> Option<f32> {
+    let normalized: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+    match normalized.trim_start_matches('+') {
+        "12V" => Some(12.0),
+        "5V" => Some(5.0),
+        "3.3V" | "3VCC" => Some(3.3),
+        _ => None,
+    }
+}
@@ -0,0 +1,165 @@
+use super::{ReadingsFuture, SensorBackend, SensorReadings};
+use std::collections::HashMap;
+use std::time::Instant;
+use sysinfo::Networks;
+
+/// Throughput and counters for one network interface
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterfaceData {
+    pub name: String,
+    pub rx_rate: f64,   // bytes/s
+    pub tx_rate: f64,   // bytes/s
+    pub rx_total: u64,  // bytes since start
+    pub tx_total: u64,  // bytes since start
+    pub rx_errors: u64, // since start
+    pub tx_errors: u64, // since start
+}
+
+/// Cumulative counters as reported by the OS
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetworkCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+}
+
+impl NetworkCounters {
+    /// Counters gained since `earlier`. Counters reset when an interface is re-created,
+    /// that counts as no traffic rather than wrapping around
+    fn since(&self, earlier: &NetworkCounters) -> NetworkCounters {
+        NetworkCounters {
+            rx_bytes: self.rx_bytes.saturating_sub(earlier.rx_bytes),
+            tx_bytes: self.tx_bytes.saturating_sub(earlier.tx_bytes),
+            rx_errors: self.rx_errors.saturating_sub(earlier.rx_errors),
+            tx_errors: self.tx_errors.saturating_sub(earlier.tx_errors),
+        }
+    }
+
+    fn plus(&self, other: &NetworkCounters) -> NetworkCounters {
+        NetworkCounters {
+            rx_bytes: self.rx_bytes + other.rx_bytes,
+            tx_bytes: self.tx_bytes + other.tx_bytes,
+            rx_errors: self.rx_errors + other.rx_errors,
+            tx_errors: self.tx_errors + other.tx_errors,
+        }
+    }
+
+    fn went_backwards(&self, earlier: &NetworkCounters) -> bool {
+        self.rx_bytes < earlier.rx_bytes
+            || self.tx_bytes < earlier.tx_bytes
+            || self.rx_errors < earlier.rx_errors
+            || self.tx_errors < earlier.tx_errors
+    }
+}
+
+/// Turns cumulative counters into rates and totals since the first sample
+#[derive(Debug, Default)]
+pub struct NetworkTracker {
+    start: HashMap<String, NetworkCounters>,
+    // Totals from before the counters were last reset
+    carried: HashMap<String, NetworkCounters>,
+    previous: HashMap<String, (NetworkCounters, Instant)>,
+}
+
+impl NetworkTracker {
+    /// Interfaces that never moved a byte (disconnected or virtual adapters) and
+    /// loopback are left out. Rates need two samples and are 0 on the first one
+    pub fn read(
+        &mut self,
+        counters: Vec<(String, NetworkCounters)>,
+        now: Instant,
+    ) -> Vec<InterfaceData> {
+        let mut interfaces = Vec::new();
+        for (name, current) in counters {
+            if is_loopback(&name) || current.rx_bytes + current.tx_bytes == 0 {
+                continue;
+            }
+
+            let mut start = *self.start.entry(name.clone()).or_insert(current);
+            // After a reset the start snapshot moves to the new counters, traffic up to
+            // the reset is kept in the totals
+            if let Some((previous, _)) = self.previous.get(&name) {
+                if current.went_backwards(previous) {
+                    let carried = self.carried.entry(name.clone()).or_default();
+                    *carried = carried.plus(&previous.since(&start));
+                    start = current;
+                    self.start.insert(name.clone(), start);
+                }
+            }
+            let (rx_rate, tx_rate) = match self.previous.get(&name) {
+                Some((previous, at)) if now > *at => {
+                    let delta = current.since(previous);
+                    let secs = (now - *at).as_secs_f64();
+                    (delta.rx_bytes as f64 / secs, delta.tx_bytes as f64 / secs)
+                }
+                _ => (0.0, 0.0),
+            };
+            self.previous.insert(name.clone(), (current, now));
+
+            let carried = self.carried.get(&name).copied().unwrap_or_default();
+            let total = carried.plus(&current.since(&start));
+            interfaces.push(InterfaceData {
+                name,
+                rx_rate,
+                tx_rate,
+                rx_total: total.rx_bytes,
+                tx_total: total.tx_bytes,
+                rx_errors: total.rx_errors,
+                tx_errors: total.tx_errors,
+            });
+        }
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        interfaces
+    }
+}
+
+fn is_loopback(name: &str) -> bool {
+    name == "lo" || name.starts_with("Loopback")
+}
+
+/// Per-interface throughput from sysinfo
+pub struct NetworkBackend {
+    networks: Networks,
+    tracker: NetworkTracker,
+}
+
+impl NetworkBackend {
+    pub fn new() -> Self {
+        Self {
+            networks: Networks::new_with_refreshed_list(),
+            tracker: NetworkTracker::default(),
+        }
+    }
+}
+
+impl Default for NetworkBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SensorBackend for NetworkBackend {
+    fn poll(&mut self) -> ReadingsFuture {
+        self.networks.refresh(true);
+        let counters = self
+            .networks
+            .iter()
+            .map(|(name, data)| {
+                let counters = NetworkCounters {
+                    rx_bytes: data.total_received(),
+                    tx_bytes: data.total_transmitted(),
+                    rx_errors: data.total_errors_on_received(),
+                    tx_errors: data.total_errors_on_transmitted(),
+                };
+                (name.clone(), counters)
+            })
+            .collect();
+
+        let readings = SensorReadings {
+            network: Some(self.tracker.read(counters, Instant::now())),
+            ..Default::default()
+        };
+        Box::pin(std::future::ready(Ok(readings)))
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+use sysinfo::System;
+
+pub const BYTES_PER_MB: f32 = 1024.0 * 1024.0;
+/// How many of the busiest and of the largest processes are tracked each tick, also
+/// the number of top memory consumers
+pub const TOP_PROCESS_COUNT: usize = 10;
+
+/// CPU, memory and estimated power draw of one running process
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessData {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32, // % of all logical processors
+    pub memory: f32,    // MB
+    // Fraction of the CPU time used by all processes that went to this one
+    pub cpu_share: f32,
+    pub power_draw: Option<f32>, // W, estimated from package power
+}
+
+impl ProcessData {
+    pub fn new(pid: u32, name: impl Into<String>, cpu_usage: f32, memory_bytes: u64) -> Self {
+        Self {
+            pid,
+            name: name.into(),
+            cpu_usage,
+            memory: memory_bytes as f32 / BYTES_PER_MB,
+            cpu_share: 0.0,
+            power_draw: None,
+        }
+    }
+}
+
+/// Top processes from an already refreshed `System`. Process CPU usage is measured
+/// between two refreshes, so it's 0 for every process on the first one
+pub fn processes_from_system(system: &System) -> Vec<ProcessData> {
+    // sysinfo reports process usage per logical processor (up to 100% * count)
+    let cpu_count = system.cpus().len().max(1) as f32;
+    let processes = system
+        .processes()
+        .iter()
+        .map(|(pid, process)| {
+            ProcessData::new(
+                pid.as_u32(),
+                process.name().to_string_lossy(),
+                process.cpu_usage() / cpu_count,
+                process.memory(),
+            )
+        })
+        .collect();
+    top_processes(processes, TOP_PROCESS_COUNT)
+}
+
+/// The `count` busiest and the `count` largest processes, busiest first. `cpu_share` is
+/// relative to all given processes, not only the returned ones
+pub fn top_processes(mut processes: Vec<ProcessData>, count: usize) -> Vec<ProcessData> {
+    let total_cpu: f32 = processes.iter().map(|p| p.cpu_usage).sum();
+    for process in &mut processes {
+        process.cpu_share = if total_cpu > 0.0 {
+            process.cpu_usage / total_cpu
+        } else {
+            0.0
+        };
+    }
+
+    let largest: HashSet<u32> = top_n(processes.iter().collect(), count, |p| p.memory)
+        .iter()
+        .map(|p| p.pid)
+        .collect();
+
+    processes.sort_by(|a, b| {
+        b.cpu_usage
+            .total_cmp(&a.cpu_usage)
+            .then_with(|| b.memory.total_cmp(&a.memory))
+    });
+    processes
+        .into_iter()
+        .enumerate()
+        .filter(|(rank, process)| *rank < count || largest.contains(&process.pid))
+        .map(|(_, process)| process)
+        .collect()
+}
+
+/// The `count` items with the largest `key`, largest first. Ties keep their order
+pub fn top_n<T>(mut items: Vec<T>, count: usize, key: impl Fn(&T) -> f32) -> Vec<T> {
+    items.sort_by(|a, b| key(b).total_cmp(&key(a)));
+    items.truncate(count);
+    items
+}
+
+/// Split the package power between processes by their share of CPU time.
+/// Idle and uncore power is included, so this is an estimate, not a measurement
+pub fn attribute_power(processes: &mut [ProcessData], package_power: Option<f32>) {
+    for process in processes {
+        process.power_draw = package_power.map(|power| power * process.cpu_share);
+    }
+}
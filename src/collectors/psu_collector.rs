@@ -0,0 +1,73 @@
+use super::{SensorKind, SensorSample};
+
+/// Readings for a smart PSU (Corsair HXi/RMi/AXi, NZXT E series, ...)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PsuData {
+    pub name: String,
+    pub input_power: Option<f32>,  // W, drawn from the wall
+    pub output_power: Option<f32>, // W, delivered to the system
+    pub efficiency: Option<f32>,   // %
+    pub fan_rpm: Option<f32>,
+    pub temp: Option<f32>, // Hottest PSU sensor
+}
+
+impl PsuData {
+    /// Build PSU readings from the sensor list of one PSU. Models report either input
+    /// power or efficiency, the missing one is calculated from the other
+    pub fn from_sensors(name: impl Into<String>, sensors: &[SensorSample]) -> Self {
+        let find = |kind: SensorKind, names: &[&str]| {
+            names.iter().find_map(|wanted| {
+                sensors
+                    .iter()
+                    .find(|s| s.kind == kind && s.name == *wanted)
+                    .map(|s| s.value)
+            })
+        };
+
+        let output_power = find(
+            SensorKind::Power,
+            &["Total Output", "Output", "Total Watts"],
+        );
+        let mut input_power = find(SensorKind::Power, &["Input", "Total Input", "Input Power"]);
+        let mut efficiency = find(SensorKind::Level, &["Efficiency"]);
+        match (input_power, output_power, efficiency) {
+            (Some(input), Some(output), None) if input > 0.0 => {
+                efficiency = Some(output / input * 100.0);
+            }
+            (None, Some(output), Some(eff)) if eff > 0.0 => {
+                input_power = Some(output / eff * 100.0);
+            }
+            _ => {}
+        }
+
+        Self {
+            name: name.into(),
+            input_power,
+            output_power,
+            efficiency,
+            fan_rpm: sensors
+                .iter()
+                .find(|s| s.kind == SensorKind::Fan)
+                .map(|s| s.value),
+            temp: sensors
+                .iter()
+                .filter(|s| s.kind == SensorKind::Temperature)
+                .map(|s| s.value)
+                .reduce(f32::max),
+        }
+    }
+}
+
+/// Total wall draw of all PSUs, `None` when no PSU reports it
+pub fn total_wall_draw(psus: &[PsuData]) -> Option<f32> {
+    psus.iter()
+        .filter_map(|p| p.input_power)
+        .reduce(|a, b| a + b)
+}
+
+/// Share of the wall draw used by the CPU package (%)
+pub fn cpu_share_of_wall(cpu_power: f32, wall_draw: Option<f32>) -> Option<f32> {
+    wall_draw
+        .filter(|wall| *wall > 0.0)
+        .map(|wall| cpu_power / wall * 100.0)
+}
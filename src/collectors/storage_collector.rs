@@ -0,0 +1,51 @@
+use super::{SensorKind, SensorSample};
+
+/// Readings for a single drive. SMART values depend on the drive type: NVMe drives
+/// report "Percentage Used" and "Available Spare", SATA SSDs "Remaining Life"
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StorageData {
+    pub name: String,
+    pub temp: Option<f32>,
+    pub used_space: Option<f32>,      // %
+    pub remaining_life: Option<f32>,  // %
+    pub available_spare: Option<f32>, // %
+    pub data_written: Option<f32>,    // GB
+}
+
+impl StorageData {
+    /// Build drive readings from the sensor list of one drive
+    pub fn from_sensors(name: impl Into<String>, sensors: &[SensorSample]) -> Self {
+        let find = |kind: SensorKind, names: &[&str]| {
+            names.iter().find_map(|wanted| {
+                sensors
+                    .iter()
+                    .find(|s| s.kind == kind && s.name == *wanted)
+                    .map(|s| s.value)
+            })
+        };
+
+        // NVMe drives have several temperature sensors, "Temperature" is the composite one
+        let temp = find(SensorKind::Temperature, &["Temperature"]).or_else(|| {
+            sensors
+                .iter()
+                .find(|s| s.kind == SensorKind::Temperature)
+                .map(|s| s.value)
+        });
+        // NVMe wear is reported as used endurance, convert to remaining life
+        let remaining_life = find(SensorKind::Level, &["Remaining Life"]).or_else(|| {
+            find(SensorKind::Level, &["Percentage Used"]).map(|used| (100.0 - used).max(0.0))
+        });
+
+        Self {
+            name: name.into(),
+            temp,
+            used_space: find(SensorKind::Load, &["Used Space"]),
+            remaining_life,
+            available_spare: find(SensorKind::Level, &["Available Spare"]),
+            data_written: find(
+                SensorKind::Data,
+                &["Data Written", "Total Bytes Written", "Host Writes"],
+            ),
+        }
+    }
+}
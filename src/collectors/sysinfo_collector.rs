@@ -0,0 +1,44 @@
+use super::memory_collector::MemoryData;
+use super::process_collector::processes_from_system;
+use super::{CoreStats, ReadingsFuture, SensorBackend, SensorReadings};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Global and per-core CPU usage, memory usage and top processes from sysinfo
+pub struct SysinfoBackend {
+    system: System,
+}
+
+impl SysinfoBackend {
+    pub fn new(system: System) -> Self {
+        Self { system }
+    }
+}
+
+impl SensorBackend for SysinfoBackend {
+    fn poll(&mut self) -> ReadingsFuture {
+        self.system.refresh_cpu_all();
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+        let cores: Vec<CoreStats> = self
+            .system
+            .cpus()
+            .iter()
+            .map(|cpu| CoreStats {
+                name: cpu.name().to_string(),
+                value: cpu.cpu_usage(),
+            })
+            .collect();
+
+        let readings = SensorReadings {
+            usage: Some(self.system.global_cpu_usage()),
+            core_utilization: Some(cores),
+            memory: Some(MemoryData::from_system(&mut self.system)),
+            processes: Some(processes_from_system(&self.system)),
+            ..Default::default()
+        };
+        Box::pin(std::future::ready(Ok(readings)))
+    }
+}
@@ -0,0 +1,283 @@
+use super::CoreStats;
+use std::collections::BTreeMap;
+
+/// Performance or efficiency core on hybrid CPUs (Intel Alder Lake and later).
+/// Every core is `Performance` on non-hybrid CPUs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CoreType {
+    Performance,
+    Efficiency,
+}
+
+/// What the OS reports about one logical processor
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicalCpuInfo {
+    pub index: usize,
+    // Logical processors sharing a physical core have the same id
+    pub core_id: u64,
+    // CCD on AMD (shared L3), E-core module on Intel (shared L2)
+    pub cluster_id: u64,
+    // Higher is faster, all equal on non-hybrid CPUs
+    pub efficiency_class: u8,
+}
+
+/// Topology of one logical processor
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreTopology {
+    pub index: usize,
+    pub core_type: CoreType,
+    pub physical_core: usize,
+    // Second (or later) hardware thread of its physical core
+    pub smt_sibling: bool,
+    pub cluster: usize,
+}
+
+/// A set of logical processors with the same core type and cluster
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreGroup {
+    pub label: String,
+    pub core_type: CoreType,
+    pub cluster: usize,
+    pub members: Vec<usize>, // logical processor indices
+}
+
+/// Average and maximum of a per-core value over one group
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupStats {
+    pub label: String,
+    pub average: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuTopology {
+    pub cores: Vec<CoreTopology>, // Ordered by logical index
+}
+
+impl CpuTopology {
+    pub fn from_logical(mut cpus: Vec<LogicalCpuInfo>) -> Self {
+        cpus.sort_by_key(|cpu| cpu.index);
+        let top_class = cpus.iter().map(|cpu| cpu.efficiency_class).max();
+
+        // Dense numbering in order of first appearance
+        let mut physical_cores: Vec<u64> = Vec::new();
+        let mut clusters: Vec<u64> = Vec::new();
+        let cores = cpus
+            .iter()
+            .map(|cpu| {
+                let smt_sibling = physical_cores.contains(&cpu.core_id);
+                if !smt_sibling {
+                    physical_cores.push(cpu.core_id);
+                }
+                if !clusters.contains(&cpu.cluster_id) {
+                    clusters.push(cpu.cluster_id);
+                }
+                CoreTopology {
+                    index: cpu.index,
+                    core_type: if Some(cpu.efficiency_class) == top_class {
+                        CoreType::Performance
+                    } else {
+                        CoreType::Efficiency
+                    },
+                    physical_core: physical_cores
+                        .iter()
+                        .position(|id| *id == cpu.core_id)
+                        .unwrap(),
+                    smt_sibling,
+                    cluster: clusters
+                        .iter()
+                        .position(|id| *id == cpu.cluster_id)
+                        .unwrap(),
+                }
+            })
+            .collect();
+        Self { cores }
+    }
+
+    pub fn is_hybrid(&self) -> bool {
+        self.cores
+            .iter()
+            .any(|core| core.core_type == CoreType::Efficiency)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&CoreTopology> {
+        self.cores.iter().find(|core| core.index == index)
+    }
+
+    /// Topology of each entry in a per-core value list. Lists have one entry per logical
+    /// processor (usage, PDH/cpufreq clocks) or one per physical core (LHM temperatures
+    /// and power). `None` when the list doesn't line up with either
+    pub fn entries(&self, len: usize) -> Option<Vec<&CoreTopology>> {
+        if len == 0 {
+            return None;
+        }
+        if len == self.cores.len() {
+            return Some(self.cores.iter().collect());
+        }
+        let first_threads: Vec<&CoreTopology> =
+            self.cores.iter().filter(|core| !core.smt_sibling).collect();
+        (len == first_threads.len()).then_some(first_threads)
+    }
+
+    /// Entries of a per-core value list grouped by core type, then cluster. P-cores come
+    /// first. Lists that don't match the topology end up in a single "Cores" group
+    pub fn groups(&self, len: usize) -> Vec<CoreGroup> {
+        let Some(entries) = self.entries(len) else {
+            return vec![CoreGroup {
+                label: "Cores".to_string(),
+                core_type: CoreType::Performance,
+                cluster: 0,
+                members: (0..len).collect(),
+            }];
+        };
+
+        let cluster_count = self
+            .cores
+            .iter()
+            .map(|c| c.cluster)
+            .max()
+            .map_or(0, |c| c + 1);
+        let mut groups: BTreeMap<(CoreType, usize), Vec<usize>> = BTreeMap::new();
+        for (position, core) in entries.iter().enumerate() {
+            groups
+                .entry((core.core_type, core.cluster))
+                .or_default()
+                .push(position);
+        }
+
+        let hybrid = self.is_hybrid();
+        groups
+            .into_iter()
+            .map(|((core_type, cluster), members)| {
+                let kind = match (hybrid, core_type) {
+                    (false, _) => "Cores",
+                    (true, CoreType::Performance) => "P-cores",
+                    (true, CoreType::Efficiency) => "E-cores",
+                };
+                let label = if cluster_count > 1 {
+                    format!("{} (cluster {})", kind, cluster)
+                } else {
+                    kind.to_string()
+                };
+                CoreGroup {
+                    label,
+                    core_type,
+                    cluster,
+                    members,
+                }
+            })
+            .collect()
+    }
+
+    /// Per-group average and max of a per-core value (usage, clock, temperature, ...)
+    pub fn aggregate(&self, values: &[CoreStats]) -> Vec<GroupStats> {
+        self.groups(values.len())
+            .into_iter()
+            .filter(|group| !group.members.is_empty())
+            .map(|group| {
+                let member_values: Vec<f32> =
+                    group.members.iter().map(|i| values[*i].value).collect();
+                GroupStats {
+                    label: group.label,
+                    average: member_values.iter().sum::<f32>() / member_values.len() as f32,
+                    max: member_values.iter().copied().fold(f32::MIN, f32::max),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Topology from GetLogicalProcessorInformationEx. Logical processors are numbered
+/// group by group, the same order PDH reports them in
+#[cfg(windows)]
+pub fn read_windows_topology() -> CpuTopology {
+    use windows::Win32::System::SystemInformation::{
+        CacheUnified, GetLogicalProcessorInformationEx, RelationAll, RelationCache,
+        RelationProcessorCore, GROUP_AFFINITY, SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX,
+    };
+
+    // Logical processor indices of an affinity mask
+    fn mask_indices(affinity: &GROUP_AFFINITY) -> impl Iterator<Item = usize> + '_ {
+        (0..usize::BITS as usize)
+            .filter(move |bit| affinity.Mask & (1 << bit) != 0)
+            .map(move |bit| affinity.Group as usize * usize::BITS as usize + bit)
+    }
+
+    unsafe {
+        // First call only reports the needed buffer size
+        let mut length: u32 = 0;
+        let _ = GetLogicalProcessorInformationEx(RelationAll, None, &mut length);
+        let mut buffer: Vec<u8> = vec![0; length as usize];
+        if GetLogicalProcessorInformationEx(
+            RelationAll,
+            Some(buffer.as_mut_ptr() as *mut SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX),
+            &mut length,
+        )
+        .is_err()
+        {
+            return CpuTopology::default();
+        }
+
+        // Records are variable sized, each one starts with its relationship and size
+        let mut cpus: BTreeMap<usize, LogicalCpuInfo> = BTreeMap::new();
+        // (logical processor, cache id) of the L2 and L3 caches, ids count per level
+        let mut l2_caches: Vec<(usize, u64)> = Vec::new();
+        let mut l3_caches: Vec<(usize, u64)> = Vec::new();
+        let mut cache_counts = [0u64; 2];
+        let mut offset = 0;
+        let mut core_id = 0;
+        while offset < length as usize {
+            let info =
+                &*(buffer.as_ptr().add(offset) as *const SYSTEM_LOGICAL_PROCESSOR_INFORMATION_EX);
+            if info.Relationship == RelationProcessorCore {
+                let processor = &info.Anonymous.Processor;
+                let masks = std::slice::from_raw_parts(
+                    processor.GroupMask.as_ptr(),
+                    processor.GroupCount as usize,
+                );
+                for index in masks.iter().flat_map(mask_indices) {
+                    cpus.insert(
+                        index,
+                        LogicalCpuInfo {
+                            index,
+                            core_id,
+                            cluster_id: 0,
+                            efficiency_class: processor.EfficiencyClass,
+                        },
+                    );
+                }
+                core_id += 1;
+            } else if info.Relationship == RelationCache {
+                let cache = &info.Anonymous.Cache;
+                if (cache.Level == 2 || cache.Level == 3) && cache.Type == CacheUnified {
+                    let level = usize::from(cache.Level - 2);
+                    let cache_id = (u64::from(cache.Level) << 24) | cache_counts[level];
+                    cache_counts[level] += 1;
+                    let caches = if cache.Level == 2 {
+                        &mut l2_caches
+                    } else {
+                        &mut l3_caches
+                    };
+                    for index in mask_indices(&cache.Anonymous.GroupMask) {
+                        caches.push((index, cache_id));
+                    }
+                }
+            }
+            offset += info.Size as usize;
+        }
+
+        // E-core modules share an L2, CCDs (and everything else) an L3
+        let top_class = cpus.values().map(|cpu| cpu.efficiency_class).max();
+        for cpu in cpus.values_mut() {
+            let caches = if Some(cpu.efficiency_class) == top_class {
+                &l3_caches
+            } else {
+                &l2_caches
+            };
+            if let Some((_, cache_id)) = caches.iter().find(|(index, _)| *index == cpu.index) {
+                cpu.cluster_id = *cache_id;
+            }
+        }
+        CpuTopology::from_logical(cpus.into_values().collect())
+    }
+}
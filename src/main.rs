@@ -5,61 +5,67 @@ mod collectors;
 mod utils;
 
 use crate::app::plot_window;
+use crate::app::process_window::{ProcessWindow, ProcessWindowMessage};
+use crate::collectors::battery_collector::BatteryData;
 use crate::collectors::cpu_collector::CpuData;
-use crate::collectors::lhm_collector::lhm_cpu_queries;
-use crate::collectors::CoreStats;
-use crate::utils::csv_logger::{CsvCpuLogEntry, CsvLogger};
+#[cfg(windows)]
+use crate::collectors::cpu_frequency_collector::FrequencyMonitor;
+use crate::collectors::gpu_collector::GpuData;
+#[cfg(target_os = "linux")]
+use crate::collectors::hwmon_collector::{HwmonBackend, SYSFS_ROOT};
+#[cfg(windows)]
+use crate::collectors::lhm_collector::{connect_with_backoff, LhmBackend};
+use crate::collectors::memory_collector::MemoryData;
+use crate::collectors::motherboard_collector::{MotherboardData, RAIL_TOLERANCE};
+use crate::collectors::network_collector::{InterfaceData, NetworkBackend};
+use crate::collectors::process_collector::{attribute_power, top_n, ProcessData, BYTES_PER_MB};
+use crate::collectors::psu_collector::{total_wall_draw, PsuData};
+use crate::collectors::storage_collector::StorageData;
+use crate::collectors::sysinfo_collector::SysinfoBackend;
+#[cfg(windows)]
+use crate::collectors::topology_collector::read_windows_topology;
+use crate::collectors::{SensorBackend, SensorError, SensorReadings};
+use crate::utils::alert_engine::{
+    motherboard_rules, temperature_rule, user_rules, AlertEngine, AlertRule, Metric,
+    TEMPERATURE_RULE_NAME,
+};
+use crate::utils::csv_logger::{
+    archive_finished_logs, join_values, CsvCpuLogEntry, CsvLogger, CsvProcessLogEntry, CsvSchema,
+    LogCompression, MetricGroup, ProcessCsvLogger,
+};
+use crate::utils::incident_logger::{Incident, IncidentLogger};
+#[cfg(target_os = "linux")]
+use crate::utils::notifications::DbusNotifier;
+use crate::utils::notifications::{notify_alerts, NotificationSink};
+use crate::utils::retention::{compact_logs, CompactionReport, RetentionPolicy};
+use crate::utils::sqlite_store::{DeviceNames, SqliteStore};
 use app::plot_window::PlotWindowMessage;
-use app::settings::Settings;
+use app::settings::{Settings, TempUnits};
+use app::tray_menu::{TrayAction, TrayMenu};
+use app::tray_renderer::{self, TrayIconStyle};
 use app::{layout, main_window, modal};
+#[cfg(windows)]
 use colored::Colorize;
 use iced::widget::container;
 use iced::{window, Element, Subscription, Task, Theme};
+#[cfg(windows)]
 use lhm_client::service::is_service_installed;
-use lhm_client::{ComputerOptions, LHMClient};
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use sysinfo::System;
 use tray_icon::{
-    menu::{Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem},
+    menu::{MenuEvent, MenuId},
     Icon, TrayIconBuilder,
 };
 
-async fn connect_to_lhwm_service() -> Option<lhm_client::LHMClientHandle> {
-    match LHMClient::connect().await {
-        Ok(client) => {
-            println!("Connected to hardware monitoring service");
-            client
-                .set_options(ComputerOptions {
-                    controller_enabled: false,
-                    cpu_enabled: true,
-                    gpu_enabled: false,
-                    motherboard_enabled: false,
-                    battery_enabled: false,
-                    memory_enabled: false,
-                    network_enabled: false,
-                    psu_enabled: true,
-                    storage_enabled: false,
-                })
-                .await
-                .unwrap();
-            client.update_all().await.unwrap();
-            println!("{}", "Service options set".green().bold());
-            Some(client)
-        }
-        Err(e) => {
-            eprintln!("{} {}", "Failed to connect to service: {}".red(), e);
-            eprintln!("{}", "The service may not be running. Try:".red());
-            eprintln!("{}", "1. Run 'install-service.bat' as administrator".red());
-            eprintln!(
-                "{}",
-                "2. Or manually start the service from Services (services.msc)".red()
-            );
-            None
-        }
-    }
-}
+/// How many of the busiest processes are saved with each high temperature incident
+const INCIDENT_PROCESS_COUNT: usize = 5;
+/// Consecutive polls a backend may report the service as disconnected before it's
+/// dropped and the connection is made again
+const MAX_DISCONNECTED_POLLS: u32 = 3;
 
 fn main() -> iced::Result {
+    #[cfg(windows)]
     match is_service_installed() {
         Ok(true) => {
             println!("{}", "✓ Service is ready".green());
@@ -76,11 +82,11 @@ fn main() -> iced::Result {
                     .bold()
             );
             // TODO: Show user a dialog or instructions
-            std::process::exit(1);
+            eprintln!("{}", "Running without temperature and power data".red());
         }
         Err(e) => {
             eprintln!("Error checking service: {}", e);
-            std::process::exit(1);
+            eprintln!("{}", "Running without temperature and power data".red());
         }
     }
     iced::daemon(App::new, App::update, App::view)
@@ -98,54 +104,146 @@ enum AppMessage {
     ShowSettingsModal,
     HideSettingsModal,
     ThemeChanged(Theme),
+    TrayIconStyleSelected(TrayIconStyle),
     ToggleStartWithWindows(bool),
     ToggleStartMinimized(bool),
-    TempUnitSelected(app::settings::TempUnits),
+    ToggleProcessLogging(bool),
+    ToggleSqliteStorage(bool),
+    ToggleLoggedMetric(MetricGroup, bool),
+    LogCompressionSelected(LogCompression),
+    ToggleRetention(bool),
+    RetentionRawDaysChanged(String),
+    RetentionMinuteDaysChanged(String),
+    RetentionDeleteDaysChanged(String),
+    CompactLogs,
+    LogsCompacted(Result<CompactionReport, String>),
+    ToggleAlerts(bool),
+    ToggleAlertNotification(String, bool),
+    TempUnitSelected(TempUnits),
     TempLowThresholdChanged(String),
     TempHighThresholdChanged(String),
     UpdateIntervalChanged(f32),
+    ToggleBatteryProfile(bool),
+    BatteryUpdateIntervalChanged(f32),
+    BatteryTempLowThresholdChanged(String),
+    BatteryTempHighThresholdChanged(String),
     SaveSettings,
     MainButtonPressed,
     PlotterButtonPressed,
+    ProcessesButtonPressed,
     UpdateHardwareData,
-    CpuValuesUpdated((f32, f32, Vec<CoreStats>)),
+    CpuValuesUpdated(Vec<Result<SensorReadings, SensorError>>),
     MainWindow(main_window::MainWindowMessage),
     PlotWindow(PlotWindowMessage),
-    HardwareMonitorConnected(Option<lhm_client::LHMClientHandle>),
+    ProcessWindow(ProcessWindowMessage),
+    #[cfg(windows)]
+    HardwareMonitorConnected(lhm_client::LHMClientHandle),
 }
 #[derive(Clone, Debug)]
 enum Screen {
     Main,
     Plotter,
+    Processes,
 }
 
 struct App {
     window_id: Option<window::Id>,
-    hw_monitor_service: Option<lhm_client::LHMClientHandle>,
+    backends: Vec<Box<dyn SensorBackend>>,
     cpu_data: CpuData,
-    system: System,
+    memory: MemoryData,
+    gpus: Vec<GpuData>,
+    drives: Vec<StorageData>,
+    motherboard: MotherboardData,
+    network: Vec<InterfaceData>,
+    batteries: Vec<BatteryData>,
+    psus: Vec<PsuData>,
+    processes: Vec<ProcessData>,
     current_screen: Screen,
     show_settings_modal: bool,
     current_theme: Theme,
     settings: Settings,
     main_window: main_window::MainWindow,
     plot_window: plot_window::PlotWindow,
+    process_window: ProcessWindow,
     tray_icon: tray_icon::TrayIcon,
-    show_menu_id: MenuId,
-    quit_menu_id: MenuId,
+    logo: image::RgbaImage,
+    // Last image set on the tray icon, only changed images are pushed to the OS
+    tray_image: Option<image::RgbaImage>,
+    tray_menu: TrayMenu,
     csv_logger: CsvLogger,
+    // Paused from the tray menu, not saved
+    logging_paused: bool,
+    // Only while process logging is enabled in settings
+    process_logger: Option<ProcessCsvLogger>,
+    // Only while SQLite storage is enabled in settings
+    sqlite_store: Option<SqliteStore>,
+    incident_logger: IncidentLogger,
+    alert_engine: AlertEngine,
+    // Desktop notifications for fired alerts, None when there's no notification service
+    notifier: Option<Box<dyn NotificationSink>>,
+    // Polls in a row where a backend lost its service, see MAX_DISCONNECTED_POLLS
+    disconnected_polls: u32,
     last_error: Option<String>,
 }
 
 impl App {
-    /// Update tray tooltip with live hw data
-    // Temperature thresholds for icon color changes are configurable in settings
+    /// Running from the battery (laptop unplugged)
+    fn on_battery(&self) -> bool {
+        self.batteries.iter().any(|b| b.on_battery)
+    }
+
+    /// Update tray tooltip and menu with live hw data
     fn update_tray_tooltip(&self) {
+        let temp = if self.cpu_data.stale {
+            format!("{:.0}°C (stale)", self.cpu_data.temp)
+        } else if self.cpu_data.temp_available {
+            format!("{:.0}°C", self.cpu_data.temp)
+        } else {
+            "unavailable".to_string()
+        };
+        let power = if self.cpu_data.power_available && self.cpu_data.stale {
+            format!("{:.1}W (stale)", self.cpu_data.total_power_draw)
+        } else if self.cpu_data.power_available {
+            format!("{:.1}W", self.cpu_data.total_power_draw)
+        } else {
+            "unavailable".to_string()
+        };
+        self.tray_menu.set_readouts(
+            &temp,
+            &format!("{:.0}%", self.cpu_data.usage),
+            &power,
+            &format!("{:.2} GHz", self.cpu_data.current_frequency),
+        );
+        // Follows the settings modal and switches between AC and battery
+        self.tray_menu
+            .set_update_interval(self.settings.active_update_interval(self.on_battery()));
+        let power = match total_wall_draw(&self.psus) {
+            Some(wall) => format!("{} ({:.0}W wall)", power, wall),
+            None => power,
+        };
         let mut tooltip = format!(
-            "CPU: {:.0}°C ({:.0}%)\nPower: {:.1}W",
-            self.cpu_data.temp, self.cpu_data.usage, self.cpu_data.total_power_draw
+            "CPU: {} ({:.0}%)\nPower: {}\nRAM: {:.1}/{:.1} GB",
+            temp, self.cpu_data.usage, power, self.memory.used, self.memory.total
         );
 
+        if let Some(battery) = self.batteries.first() {
+            let source = if self.on_battery() {
+                "on battery"
+            } else {
+                "AC"
+            };
+            tooltip.push_str(&format!("\nBattery: {} {}", battery.summary(), source));
+        }
+
+        for rule in self.alert_engine.firing() {
+            tooltip.push_str(&format!("\n⚠ {}", rule.name));
+        }
+
+        // Stalled fans and out of spec voltage rails
+        for warning in self.motherboard.warnings() {
+            tooltip.push_str(&format!("\n⚠ {}", warning));
+        }
+
         // Append error message if present
         if let Some(error) = &self.last_error {
             tooltip.push_str(&format!("\n⚠ Error: {}", error));
@@ -156,6 +254,261 @@ impl App {
         }
     }
 
+    /// Re-render the tray icon for the current temperature and thresholds
+    fn update_tray_icon(&mut self) {
+        let unit = self
+            .settings
+            .selected_temp_units
+            .unwrap_or(TempUnits::Celsius);
+        // Sensor values are Celsius, thresholds are in the selected unit
+        let temp = (self.cpu_data.temp_available && !self.cpu_data.stale)
+            .then(|| TempUnits::Celsius.convert(self.cpu_data.temp, unit));
+        let (low, high) = self.settings.active_temp_thresholds(self.on_battery());
+        let image = tray_renderer::render(self.settings.tray_icon_style, temp, low, high)
+            .unwrap_or_else(|| self.logo.clone());
+        if self.tray_image.as_ref() == Some(&image) {
+            return;
+        }
+
+        let (width, height) = image.dimensions();
+        match Icon::from_rgba(image.as_raw().clone(), width, height) {
+            Ok(icon) => {
+                if let Err(e) = self.tray_icon.set_icon(Some(icon)) {
+                    eprintln!("Failed to update tray icon: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to create tray icon: {}", e),
+        }
+        self.tray_image = Some(image);
+    }
+
+    /// High temperature threshold for the current power source in °C.
+    /// Thresholds are stored in the selected unit, sensor values are Celsius
+    fn high_threshold_celsius(&self) -> f32 {
+        let (_, high) = self.settings.active_temp_thresholds(self.on_battery());
+        self.settings
+            .selected_temp_units
+            .map_or(high, |unit| unit.convert(high, TempUnits::Celsius))
+    }
+
+    /// High temperature alert from the thresholds in settings, the fan and rail alerts,
+    /// then the configured rules
+    fn alert_rules(&self) -> Vec<AlertRule> {
+        let mut rules = vec![temperature_rule(
+            self.high_threshold_celsius(),
+            self.settings.notify_temp_alert,
+        )];
+        rules.extend(motherboard_rules(RAIL_TOLERANCE));
+        rules.extend(user_rules(&self.settings.alert_rules));
+        rules
+    }
+
+    /// Run the alert rules on the merged readings
+    fn evaluate_alerts(&mut self) {
+        if !self.settings.alerts_enabled {
+            // Nothing stays firing while alerts are off
+            self.alert_engine.set_rules(Vec::new());
+            return;
+        }
+        // Thresholds follow the settings and the power source
+        self.alert_engine.set_rules(self.alert_rules());
+        let cpu = &self.cpu_data;
+        let events = self.alert_engine.evaluate(
+            |metric| match metric {
+                Metric::Temperature => (cpu.temp_available && !cpu.stale).then_some(cpu.temp),
+                Metric::Power => {
+                    (cpu.power_available && !cpu.stale).then_some(cpu.total_power_draw)
+                }
+                Metric::Usage => Some(cpu.usage),
+                Metric::Frequency => Some(cpu.current_frequency as f32),
+                Metric::FanRpm => self.motherboard.slowest_fan_rpm(),
+                Metric::RailDeviation => self.motherboard.worst_rail_deviation(),
+            },
+            Instant::now(),
+        );
+        if let Some(notifier) = self.notifier.as_deref_mut() {
+            for error in notify_alerts(notifier, &events) {
+                eprintln!("Failed to send notification: {}", error);
+            }
+        }
+    }
+
+    /// Save what was running when the temperature crossed the high threshold
+    fn capture_incident(&mut self, threshold: f32) {
+        let busiest = top_n(
+            self.processes.iter().collect::<Vec<&ProcessData>>(),
+            INCIDENT_PROCESS_COUNT,
+            |p| p.cpu_usage,
+        );
+        let top_processes = busiest
+            .iter()
+            .map(|p| format!("{} ({}) {:.1}%", p.name, p.pid, p.cpu_usage))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let incident = Incident {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            temperature: self.cpu_data.temp,
+            threshold,
+            frequency: self.cpu_data.current_frequency,
+            core_usage: join_values(self.cpu_data.core_utilization.iter().map(|c| Some(c.value))),
+            top_processes,
+        };
+        if let Err(e) = self.incident_logger.record(incident) {
+            let error_msg = format!("Incident write failed: {}", e);
+            eprintln!("{}", error_msg);
+            self.last_error = Some(error_msg);
+        }
+    }
+
+    /// Drop backends whose service stayed disconnected for MAX_DISCONNECTED_POLLS polls
+    /// and start connecting again. `readings` are in backend order
+    fn drop_disconnected_backends(
+        &mut self,
+        readings: &[Result<SensorReadings, SensorError>],
+    ) -> Task<AppMessage> {
+        let disconnected: Vec<usize> = readings
+            .iter()
+            .enumerate()
+            .filter(|(_, reading)| matches!(reading, Err(SensorError::ServiceDisconnected(_))))
+            .map(|(i, _)| i)
+            .collect();
+        if disconnected.is_empty() {
+            self.disconnected_polls = 0;
+            return Task::none();
+        }
+        self.disconnected_polls += 1;
+        // A backend added while this poll was running would shift the indices
+        if self.disconnected_polls < MAX_DISCONNECTED_POLLS || readings.len() != self.backends.len()
+        {
+            return Task::none();
+        }
+        eprintln!("Hardware monitor service lost, reconnecting");
+        for i in disconnected.into_iter().rev() {
+            self.backends.remove(i);
+        }
+        self.cpu_data.mark_unavailable();
+        self.disconnected_polls = 0;
+        connect_hardware_monitor()
+    }
+
+    /// Open the SQLite history if it isn't open yet
+    fn open_history(&mut self) {
+        if self.sqlite_store.is_none() {
+            match SqliteStore::new(None) {
+                Ok(store) => self.sqlite_store = Some(store),
+                Err(e) => self.last_error = Some(format!("SQLite open failed: {}", e)),
+            }
+        }
+    }
+
+    /// Insert a logged sample into the SQLite history, opening it on first use
+    fn store_history(&mut self, entry: &CsvCpuLogEntry) {
+        self.open_history();
+        let names = self.device_names();
+        if let Some(Err(e)) = self.sqlite_store.as_mut().map(|s| s.insert(entry, &names)) {
+            let error_msg = format!("SQLite write failed: {}", e);
+            eprintln!("{}", error_msg);
+            self.last_error = Some(error_msg);
+        }
+    }
+
+    /// Names of the devices in the per-device columns of the logged entry, same order
+    fn device_names(&self) -> DeviceNames {
+        fn names<T>(devices: &[T], name: impl Fn(&T) -> &String) -> Vec<String> {
+            devices.iter().map(|device| name(device).clone()).collect()
+        }
+        DeviceNames {
+            packages: names(&self.cpu_data.packages, |p| &p.name),
+            cores: names(&self.cpu_data.core_temps, |c| &c.name),
+            dimms: names(&self.memory.dimm_temps, |d| &d.name),
+            gpus: names(&self.gpus, |g| &g.name),
+            drives: names(&self.drives, |d| &d.name),
+            fans: names(&self.motherboard.fans, |f| &f.name),
+            voltages: names(&self.motherboard.voltages, |v| &v.name),
+            batteries: names(&self.batteries, |b| &b.name),
+            psus: names(&self.psus, |p| &p.name),
+        }
+    }
+
+    /// Append the current top processes to the process CSV, opening it on first use
+    fn log_processes(&mut self) {
+        if self.process_logger.is_none() {
+            match ProcessCsvLogger::new(None) {
+                Ok(logger) => self.process_logger = Some(logger),
+                Err(e) => {
+                    self.last_error = Some(format!("Process CSV open failed: {}", e));
+                    return;
+                }
+            }
+        }
+        let timestamp = chrono::Local::now().to_rfc3339();
+        let entries: Vec<CsvProcessLogEntry> = self
+            .processes
+            .iter()
+            .map(|process| CsvProcessLogEntry {
+                timestamp: timestamp.clone(),
+                pid: process.pid,
+                name: process.name.clone(),
+                cpu_usage: process.cpu_usage,
+                memory: process.memory,
+                power_draw: join_values([process.power_draw]),
+            })
+            .collect();
+        if let Some(Err(e)) = self.process_logger.as_mut().map(|l| l.write(&entries)) {
+            let error_msg = format!("Process CSV write failed: {}", e);
+            eprintln!("{}", error_msg);
+            self.last_error = Some(error_msg);
+        }
+    }
+
+    /// Reopen the main window if it was closed to the tray
+    fn open_window(&self) -> Task<AppMessage> {
+        if self.window_id.is_some() {
+            return Task::none();
+        }
+        let window_settings = window::Settings {
+            size: iced::Size::new(800.0, 700.0),
+            position: window::Position::Centered,
+            min_size: Some(iced::Size::new(500.0, 400.0)),
+            icon: window::icon::from_file("assets/logo.ico").ok(),
+            ..Default::default()
+        };
+        let (_, open_task) = window::open(window_settings);
+        open_task.map(AppMessage::WindowOpened)
+    }
+
+    /// Open the CSV log directory in the file manager
+    fn open_logs_folder(&self) {
+        let dir = self
+            .csv_logger
+            .path
+            .parent()
+            .unwrap_or(std::path::Path::new("logs"));
+        let dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        #[cfg(windows)]
+        let opener = "explorer";
+        #[cfg(target_os = "macos")]
+        let opener = "open";
+        #[cfg(not(any(windows, target_os = "macos")))]
+        let opener = "xdg-open";
+        if let Err(e) = std::process::Command::new(opener).arg(&dir).spawn() {
+            eprintln!("Failed to open {}: {}", dir.display(), e);
+        }
+    }
+
+    /// Tray actions have no save button, keep their changes right away. Only the
+    /// changed field is saved, unsaved edits in the settings modal stay unsaved
+    fn save_settings_from_tray(update: impl FnOnce(&mut Settings)) {
+        let result = Settings::load().and_then(|mut saved| {
+            update(&mut saved);
+            saved.save()
+        });
+        if let Err(e) = result {
+            eprintln!("Failed to save settings: {}", e);
+        }
+    }
+
     fn new() -> (Self, Task<AppMessage>) {
         let window_settings = window::Settings {
             size: iced::Size::new(800.0, 700.0),
@@ -172,25 +525,45 @@ impl App {
 
         // Load tray icon from bytes
         const ICON_DATA: &[u8] = include_bytes!("../assets/logo.ico");
-        let image = image::load_from_memory(ICON_DATA)
+        let logo = image::load_from_memory(ICON_DATA)
             .expect("Failed to load icon from memory")
             .into_rgba8();
-        let (width, height) = image.dimensions();
-        let rgba = image.into_raw();
-        let icon = Icon::from_rgba(rgba, width, height).expect("Failed to create icon");
-        // Create tray menu
-        let menu = Menu::new();
-        let show_item = MenuItem::new("Show Window", true, None);
-        let quit_item = MenuItem::new("Quit", true, None);
-        let separator = PredefinedMenuItem::separator();
-
-        // Store menu IDs for event handling
-        let show_id = show_item.id().clone();
-        let quit_id = quit_item.id().clone();
+        let (width, height) = logo.dimensions();
+        // Replaced with the live icon on the first update
+        let icon =
+            Icon::from_rgba(logo.as_raw().clone(), width, height).expect("Failed to create icon");
+        let mut system = System::new_all();
+        system.refresh_cpu_all();
+        let mut cpu_data = CpuData::new(&system);
+        // Core layout doesn't change at runtime, read it once
+        #[cfg(windows)]
+        {
+            cpu_data.topology = read_windows_topology();
+        }
 
-        menu.append_items(&[&show_item, &separator, &quit_item])
-            .expect("Failed to append menu items");
+        // Backends are polled in order, later ones override values reported by earlier ones
+        let mut backends: Vec<Box<dyn SensorBackend>> = vec![
+            Box::new(SysinfoBackend::new(system)),
+            Box::new(NetworkBackend::new()),
+        ];
+        // If PDH fails just use base frequency
+        #[cfg(windows)]
+        if let Ok(monitor) = FrequencyMonitor::new(cpu_data.base_cpu_frequency) {
+            backends.push(Box::new(monitor));
+        }
+        // LHM service is Windows only, Linux reads the same sensors from sysfs
+        #[cfg(target_os = "linux")]
+        {
+            let hwmon = HwmonBackend::new(SYSFS_ROOT);
+            cpu_data.topology = hwmon.read_topology();
+            backends.push(Box::new(hwmon));
+        }
 
+        let settings = Settings::load().expect("Error loading settings");
+        let (tray_menu, menu) = TrayMenu::new(
+            settings.active_update_interval(false),
+            settings.alerts_enabled,
+        );
         // Build tray icon
         let tray_icon = TrayIconBuilder::new()
             .with_tooltip("TempMon")
@@ -199,41 +572,61 @@ impl App {
             .build()
             .expect("Failed to create tray icon");
 
-        let mut system = System::new_all();
-        system.refresh_cpu_all();
-        let cpu_data = CpuData::new(&system);
-        let hw_monitor_service = None;
-        let settings = Settings::load().expect("Error loading settings");
         let current_theme = settings.theme.clone();
-        let csv_logger = CsvLogger::new(None).expect("Failed to create CSV logger");
-
-        // Create task to connect to hardware monitor
-        let connect_task = Task::future(async {
-            AppMessage::HardwareMonitorConnected(connect_to_lhwm_service().await)
-        });
+        let mut csv_logger = CsvLogger::with_schema(None, CsvSchema::new(&settings.logged_metrics))
+            .expect("Failed to create CSV logger");
+        let incident_logger = IncidentLogger::new(None);
+        let alert_engine = AlertEngine::new(Vec::new());
+        #[cfg(target_os = "linux")]
+        let notifier: Option<Box<dyn NotificationSink>> = match DbusNotifier::new() {
+            Ok(notifier) => Some(Box::new(notifier)),
+            Err(e) => {
+                eprintln!("Desktop notifications unavailable: {}", e);
+                None
+            }
+        };
+        #[cfg(not(target_os = "linux"))]
+        let notifier: Option<Box<dyn NotificationSink>> = None;
 
         (
             Self {
                 window_id: None,
-                hw_monitor_service,
+                backends,
                 cpu_data,
-                system,
+                memory: MemoryData::default(),
+                gpus: Vec::new(),
+                drives: Vec::new(),
+                motherboard: MotherboardData::default(),
+                network: Vec::new(),
+                batteries: Vec::new(),
+                psus: Vec::new(),
+                processes: Vec::new(),
                 current_screen: Screen::Main,
                 show_settings_modal: false,
                 current_theme,
                 settings,
                 main_window: main_window::MainWindow::new(),
                 plot_window: plot_window::PlotWindow::new(),
+                process_window: ProcessWindow::new(),
                 tray_icon,
-                show_menu_id: show_id,
-                quit_menu_id: quit_id,
+                logo,
+                tray_image: None,
+                tray_menu,
                 csv_logger,
+                logging_paused: false,
+                process_logger: None,
+                sqlite_store: None,
+                incident_logger,
+                alert_engine,
+                notifier,
+                disconnected_polls: 0,
                 last_error: None,
             },
             Task::batch(vec![
                 // Batch tasks to run in parallel
                 open_task.map(AppMessage::WindowOpened),
-                connect_task,
+                connect_hardware_monitor(),
+                Task::done(AppMessage::CompactLogs),
             ]),
         )
     }
@@ -244,15 +637,12 @@ impl App {
 
     fn update(&mut self, message: AppMessage) -> Task<AppMessage> {
         match message {
+            #[cfg(windows)]
             AppMessage::HardwareMonitorConnected(client) => {
-                self.hw_monitor_service = client;
-                if self.hw_monitor_service.is_some() {
-                    println!("{}", "✓ Connected to hardware monitor".green());
-                    // Trigger initial update after service connects
-                    Task::done(AppMessage::UpdateHardwareData)
-                } else {
-                    Task::none()
-                }
+                println!("{}", "✓ Connected to hardware monitor".green());
+                self.backends.push(Box::new(LhmBackend::new(client)));
+                // Trigger initial update after service connects
+                Task::done(AppMessage::UpdateHardwareData)
             }
             AppMessage::WindowOpened(id) => {
                 self.window_id = Some(id);
@@ -269,35 +659,72 @@ impl App {
 
                 Task::none()
             }
-            AppMessage::TrayEvent(menu_id) => {
-                if menu_id == self.show_menu_id {
-                    // If window is closed, reopen it
-                    if self.window_id.is_none() {
-                        let window_settings = window::Settings {
-                            size: iced::Size::new(800.0, 700.0),
-                            position: window::Position::Centered,
-                            min_size: Some(iced::Size::new(500.0, 400.0)),
-                            icon: window::icon::from_file("assets/logo.ico").ok(),
-                            ..Default::default()
-                        };
-                        let (_, open_task) = window::open(window_settings);
-                        return open_task.map(AppMessage::WindowOpened);
+            AppMessage::TrayEvent(menu_id) => match self.tray_menu.action(&menu_id) {
+                Some(TrayAction::ShowWindow) => self.open_window(),
+                Some(TrayAction::ShowPlots) => {
+                    self.current_screen = Screen::Plotter;
+                    self.open_window()
+                }
+                Some(TrayAction::ToggleLogging) => {
+                    self.logging_paused = !self.logging_paused;
+                    self.tray_menu.set_logging_paused(self.logging_paused);
+                    if self.logging_paused {
+                        self.process_logger = None;
+                        if let Err(e) = self.csv_logger.flush_buffer() {
+                            eprintln!("Failed to flush CSV on pause: {}", e);
+                        }
                     }
                     Task::none()
-                } else if menu_id == self.quit_menu_id {
+                }
+                Some(TrayAction::OpenLogsFolder) => {
+                    self.open_logs_folder();
+                    Task::none()
+                }
+                Some(TrayAction::SetUpdateInterval(interval)) => {
+                    // Change the interval that's in use right now
+                    let battery = self.on_battery() && self.settings.battery_profile.enabled;
+                    if battery {
+                        self.settings.battery_profile.data_update_interval = interval;
+                    } else {
+                        self.settings.data_update_interval = interval;
+                        self.settings.update_interval_input = interval.to_string();
+                    }
+                    self.tray_menu.set_update_interval(interval);
+                    Self::save_settings_from_tray(|saved| {
+                        if battery {
+                            saved.battery_profile.data_update_interval = interval;
+                        } else {
+                            saved.data_update_interval = interval;
+                        }
+                    });
+                    Task::none()
+                }
+                Some(TrayAction::ToggleAlerts) => {
+                    self.settings.alerts_enabled = !self.settings.alerts_enabled;
+                    self.tray_menu
+                        .set_alerts_enabled(self.settings.alerts_enabled);
+                    let enabled = self.settings.alerts_enabled;
+                    Self::save_settings_from_tray(|saved| saved.alerts_enabled = enabled);
+                    Task::none()
+                }
+                Some(TrayAction::Quit) => {
                     // Flush CSV buffer before quitting
                     if let Err(e) = self.csv_logger.flush_buffer() {
                         eprintln!("Failed to flush CSV on quit: {}", e);
                     }
                     std::process::exit(0);
-                } else {
-                    Task::none()
                 }
-            }
+                None => Task::none(),
+            },
             AppMessage::ThemeChanged(theme) => {
                 self.settings.theme = theme.clone();
                 Task::none()
             }
+            AppMessage::TrayIconStyleSelected(style) => {
+                self.settings.tray_icon_style = style;
+                self.update_tray_icon();
+                Task::none()
+            }
             AppMessage::ToggleStartWithWindows(enabled) => {
                 self.settings.start_with_windows = enabled;
                 Task::none()
@@ -306,6 +733,103 @@ impl App {
                 self.settings.start_minimized = enabled;
                 Task::none()
             }
+            AppMessage::ToggleProcessLogging(enabled) => {
+                self.settings.log_processes = enabled;
+                Task::none()
+            }
+            AppMessage::ToggleSqliteStorage(enabled) => {
+                self.settings.sqlite_storage = enabled;
+                Task::none()
+            }
+            AppMessage::ToggleLoggedMetric(group, enabled) => {
+                self.settings.logged_metrics.retain(|g| *g != group);
+                if enabled {
+                    self.settings.logged_metrics.push(group);
+                }
+                Task::none()
+            }
+            AppMessage::LogCompressionSelected(compression) => {
+                self.settings.log_compression = compression;
+                Task::none()
+            }
+            AppMessage::ToggleRetention(enabled) => {
+                self.settings.retention.enabled = enabled;
+                Task::none()
+            }
+            AppMessage::RetentionRawDaysChanged(value) => {
+                self.settings.retention_raw_input = value;
+                Task::none()
+            }
+            AppMessage::RetentionMinuteDaysChanged(value) => {
+                self.settings.retention_minute_input = value;
+                Task::none()
+            }
+            AppMessage::RetentionDeleteDaysChanged(value) => {
+                self.settings.retention_delete_input = value;
+                Task::none()
+            }
+            AppMessage::CompactLogs => {
+                let policy = self.settings.retention;
+                let compression = self.settings.log_compression;
+                if !policy.enabled && compression == LogCompression::None {
+                    return Task::none();
+                }
+                let dir = PathBuf::from(&self.csv_logger.dir);
+                Task::future(async move {
+                    let today = chrono::Local::now().date_naive();
+                    // Reads and rewrites whole days of logs, keep it off the UI thread.
+                    // Days that are rolled up aren't worth compressing first
+                    let result = tokio::task::spawn_blocking(move || {
+                        let mut report = if policy.enabled {
+                            compact_logs(&dir, &policy, today)?
+                        } else {
+                            CompactionReport::default()
+                        };
+                        report.compressed = archive_finished_logs(&dir, compression, today)?;
+                        anyhow::Ok(report)
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|result| result.map_err(|e| e.to_string()));
+                    AppMessage::LogsCompacted(result)
+                })
+            }
+            AppMessage::LogsCompacted(result) => {
+                match result {
+                    Ok(report) => {
+                        if report != CompactionReport::default() {
+                            println!(
+                                "Log retention: {} files rolled up, {} deleted, {} compressed",
+                                report.rolled_up, report.deleted, report.compressed
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        let error_msg = format!("Log compaction failed: {}", e);
+                        eprintln!("{}", error_msg);
+                        self.last_error = Some(error_msg);
+                    }
+                }
+                Task::none()
+            }
+            AppMessage::ToggleAlerts(enabled) => {
+                self.settings.alerts_enabled = enabled;
+                self.tray_menu.set_alerts_enabled(enabled);
+                Task::none()
+            }
+            AppMessage::ToggleAlertNotification(name, enabled) => {
+                if name == TEMPERATURE_RULE_NAME {
+                    self.settings.notify_temp_alert = enabled;
+                } else if let Some(rule) = self
+                    .settings
+                    .alert_rules
+                    .iter_mut()
+                    .find(|rule| rule.name == name)
+                {
+                    rule.notify = enabled;
+                }
+                Task::none()
+            }
             AppMessage::TempUnitSelected(unit) => {
                 // When user changes temperature unit, convert all threshold values
                 if let Some(old_unit) = self.settings.selected_temp_units {
@@ -319,6 +843,15 @@ impl App {
                         format!("{:.0}", self.settings.temp_low_threshold);
                     self.settings.temp_high_input =
                         format!("{:.0}", self.settings.temp_high_threshold);
+
+                    let battery = &mut self.settings.battery_profile;
+                    battery.temp_low_threshold = old_unit.convert(battery.temp_low_threshold, unit);
+                    battery.temp_high_threshold =
+                        old_unit.convert(battery.temp_high_threshold, unit);
+                    self.settings.battery_temp_low_input =
+                        format!("{:.0}", battery.temp_low_threshold);
+                    self.settings.battery_temp_high_input =
+                        format!("{:.0}", battery.temp_high_threshold);
                 }
 
                 self.settings.selected_temp_units = Option::from(unit);
@@ -337,6 +870,22 @@ impl App {
                 self.settings.update_interval_input = value.to_string();
                 Task::none()
             }
+            AppMessage::ToggleBatteryProfile(enabled) => {
+                self.settings.battery_profile.enabled = enabled;
+                Task::none()
+            }
+            AppMessage::BatteryUpdateIntervalChanged(value) => {
+                self.settings.battery_profile.data_update_interval = value;
+                Task::none()
+            }
+            AppMessage::BatteryTempLowThresholdChanged(value) => {
+                self.settings.battery_temp_low_input = value;
+                Task::none()
+            }
+            AppMessage::BatteryTempHighThresholdChanged(value) => {
+                self.settings.battery_temp_high_input = value;
+                Task::none()
+            }
             AppMessage::SaveSettings => {
                 // Parse and validate temperature thresholds
                 if let Ok(low) = self.settings.temp_low_input.parse::<f32>() {
@@ -349,9 +898,40 @@ impl App {
                         }
                     }
                 }
+                if let Ok(low) = self.settings.battery_temp_low_input.parse::<f32>() {
+                    if let Ok(high) = self.settings.battery_temp_high_input.parse::<f32>() {
+                        if low < high {
+                            self.settings.battery_profile.temp_low_threshold = low;
+                            self.settings.battery_profile.temp_high_threshold = high;
+                        }
+                    }
+                }
+                if let (Ok(raw_days), Ok(minute_days), Ok(delete_after_days)) = (
+                    self.settings.retention_raw_input.parse::<u32>(),
+                    self.settings.retention_minute_input.parse::<u32>(),
+                    self.settings.retention_delete_input.parse::<u32>(),
+                ) {
+                    let retention = RetentionPolicy {
+                        raw_days,
+                        minute_days,
+                        delete_after_days,
+                        ..self.settings.retention
+                    };
+                    if retention.is_valid() {
+                        self.settings.retention = retention;
+                    }
+                }
+                // New columns start a new log file, unchanged ones keep the current one
+                if let Err(e) = self
+                    .csv_logger
+                    .set_schema(CsvSchema::new(&self.settings.logged_metrics))
+                {
+                    eprintln!("Failed to switch CSV schema: {}", e);
+                }
                 Settings::save(&self.settings).expect("Error saving settings");
                 self.show_settings_modal = false;
-                Task::none()
+                // Apply a new compression or retention setting to the existing logs
+                Task::done(AppMessage::CompactLogs)
             }
             AppMessage::MainButtonPressed => {
                 self.current_screen = Screen::Main;
@@ -361,10 +941,25 @@ impl App {
                 self.current_screen = Screen::Plotter;
                 Task::none()
             }
+            AppMessage::ProcessesButtonPressed => {
+                self.current_screen = Screen::Processes;
+                Task::none()
+            }
             AppMessage::ShowSettingsModal => {
                 // Reset input fields to current saved values when opening modal
                 self.settings.temp_low_input = self.settings.temp_low_threshold.to_string();
                 self.settings.temp_high_input = self.settings.temp_high_threshold.to_string();
+                self.settings.battery_temp_low_input =
+                    self.settings.battery_profile.temp_low_threshold.to_string();
+                self.settings.battery_temp_high_input = self
+                    .settings
+                    .battery_profile
+                    .temp_high_threshold
+                    .to_string();
+                let retention = self.settings.retention;
+                self.settings.retention_raw_input = retention.raw_days.to_string();
+                self.settings.retention_minute_input = retention.minute_days.to_string();
+                self.settings.retention_delete_input = retention.delete_after_days.to_string();
                 self.show_settings_modal = true;
                 Task::none()
             }
@@ -378,29 +973,82 @@ impl App {
                 Task::none()
             }
             AppMessage::PlotWindow(msg) => {
-                self.plot_window.update(&self.csv_logger, msg);
+                // History is read from the store even when nothing was logged to it yet
+                if matches!(msg, PlotWindowMessage::RangeSelected(_))
+                    && self.settings.sqlite_storage
+                {
+                    self.open_history();
+                }
+                self.plot_window
+                    .update(&self.csv_logger, self.sqlite_store.as_ref(), msg);
+                Task::none()
+            }
+            AppMessage::ProcessWindow(msg) => {
+                self.process_window.update(msg);
                 Task::none()
             }
             AppMessage::UpdateHardwareData => {
-                self.cpu_data.update(&mut self.system);
-
-                if let Some(client) = &self.hw_monitor_service {
-                    let client = client.clone();
-                    Task::future(async move {
-                        // NOTE TO SELF: Task::future always needs to return message
-                        client.update_all().await.expect("Error updating hardware");
-                        let temps = lhm_cpu_queries(&client).await;
-                        AppMessage::CpuValuesUpdated(temps)
-                    })
-                } else {
-                    Task::none()
-                }
+                let polls: Vec<_> = self.backends.iter_mut().map(|b| b.poll()).collect();
+                Task::future(async move {
+                    // NOTE TO SELF: Task::future always needs to return message
+                    let readings = iced::futures::future::join_all(polls).await;
+                    AppMessage::CpuValuesUpdated(readings)
+                })
             }
-            AppMessage::CpuValuesUpdated(temps) => {
-                // Collect everything from lhm queries into CpuData
-                self.cpu_data.update_lhm_data(temps);
-                // Update tray tooltip with fresh hardware data
+            AppMessage::CpuValuesUpdated(readings) => {
+                // Merge readings from all backends into CpuData
+                let mut sensor_error = None;
+                for reading in &readings {
+                    match reading {
+                        Ok(reading) => {
+                            self.cpu_data.update(reading);
+                            self.memory.update(reading);
+                            if let Some(gpus) = &reading.gpus {
+                                self.gpus = gpus.clone();
+                            }
+                            if let Some(drives) = &reading.drives {
+                                self.drives = drives.clone();
+                            }
+                            if let Some(motherboard) = &reading.motherboard {
+                                self.motherboard = motherboard.clone();
+                            }
+                            if let Some(network) = &reading.network {
+                                self.network = network.clone();
+                            }
+                            if let Some(batteries) = &reading.batteries {
+                                self.batteries = batteries.clone();
+                            }
+                            if let Some(psus) = &reading.psus {
+                                self.psus = psus.clone();
+                            }
+                            if let Some(processes) = &reading.processes {
+                                self.processes = processes.clone();
+                            }
+                            for e in &reading.errors {
+                                eprintln!("Sensor poll failed: {}", e);
+                                sensor_error = Some(e.to_string());
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Sensor poll failed: {}", e);
+                            self.cpu_data.mark_stale();
+                            sensor_error = Some(e.to_string());
+                        }
+                    }
+                }
+                self.last_error = sensor_error;
+                let reconnect_task = self.drop_disconnected_backends(&readings);
+                self.evaluate_alerts();
+                // Package power is only known after all backends are merged
+                attribute_power(
+                    &mut self.processes,
+                    self.cpu_data
+                        .power_available
+                        .then_some(self.cpu_data.total_power_draw),
+                );
+                // Update tray tooltip and icon with fresh hardware data
                 self.update_tray_tooltip();
+                self.update_tray_icon();
 
                 // Log CPU data to CSV
                 let entry = CsvCpuLogEntry {
@@ -411,25 +1059,84 @@ impl App {
                         .as_ref()
                         .map(|u| u.to_string())
                         .unwrap_or_else(|| "C".to_string()),
-                    temperature: self.cpu_data.temp,
+                    // Stale values would be logged as fresh readings
+                    temperature: (self.cpu_data.temp_available && !self.cpu_data.stale)
+                        .then_some(self.cpu_data.temp),
                     cpu_usage: self.cpu_data.usage,
-                    power_draw: self.cpu_data.total_power_draw,
+                    power_draw: (self.cpu_data.power_available && !self.cpu_data.stale)
+                        .then_some(self.cpu_data.total_power_draw),
+                    package_temperatures: join_values(
+                        self.cpu_data.packages.iter().map(|p| Some(p.temp)),
+                    ),
+                    package_power_draws: join_values(
+                        self.cpu_data.packages.iter().map(|p| p.power_draw),
+                    ),
+                    core_temperatures: join_values(
+                        self.cpu_data.core_temps.iter().map(|c| Some(c.value)),
+                    ),
+                    gpu_temperatures: join_values(self.gpus.iter().map(|g| g.core_temp)),
+                    gpu_loads: join_values(self.gpus.iter().map(|g| g.load)),
+                    gpu_power_draws: join_values(self.gpus.iter().map(|g| g.power_draw)),
+                    gpu_vram_used: join_values(self.gpus.iter().map(|g| g.vram_used)),
+                    drive_temperatures: join_values(self.drives.iter().map(|d| d.temp)),
+                    drive_used_space: join_values(self.drives.iter().map(|d| d.used_space)),
+                    drive_remaining_life: join_values(self.drives.iter().map(|d| d.remaining_life)),
+                    fan_rpms: join_values(self.motherboard.fans.iter().map(|f| Some(f.rpm))),
+                    rail_voltages: join_values(
+                        self.motherboard.voltages.iter().map(|v| Some(v.volts)),
+                    ),
+                    memory_used: self.memory.used,
+                    swap_used: self.memory.swap_used,
+                    dimm_temperatures: join_values(
+                        self.memory.dimm_temps.iter().map(|d| Some(d.temp)),
+                    ),
+                    network_rx_rate: (self.network.iter().map(|i| i.rx_rate).sum::<f64>()
+                        / f64::from(BYTES_PER_MB)) as f32,
+                    network_tx_rate: (self.network.iter().map(|i| i.tx_rate).sum::<f64>()
+                        / f64::from(BYTES_PER_MB)) as f32,
+                    battery_charge_levels: join_values(
+                        self.batteries.iter().map(|b| b.charge_level),
+                    ),
+                    battery_rates: join_values(self.batteries.iter().map(|b| b.rate)),
+                    on_battery: self.on_battery(),
+                    wall_power_draw: total_wall_draw(&self.psus),
+                    psu_output_powers: join_values(self.psus.iter().map(|p| p.output_power)),
+                    psu_efficiencies: join_values(self.psus.iter().map(|p| p.efficiency)),
                 };
 
-                match self.csv_logger.write(vec![entry]) {
-                    Ok(_) => {
-                        // Clear error on successful write
-                        self.last_error = None;
-                    }
-                    Err(e) => {
+                if self.settings.sqlite_storage && !self.logging_paused {
+                    self.store_history(&entry);
+                } else {
+                    self.sqlite_store = None;
+                }
+                // Plots read the logged entries, they pause along with the file
+                if !self.logging_paused {
+                    if let Err(e) = self.csv_logger.write(vec![entry]) {
                         let error_msg = format!("CSV write failed: {}", e);
                         eprintln!("{}", error_msg);
                         self.last_error = Some(error_msg);
                     }
                 }
-                self.plot_window
-                    .update(&self.csv_logger, PlotWindowMessage::Tick);
-                Task::none()
+                self.plot_window.update(
+                    &self.csv_logger,
+                    self.sqlite_store.as_ref(),
+                    PlotWindowMessage::Tick,
+                );
+
+                // Stale values would re-trigger the same incident after a reconnect
+                if self.cpu_data.temp_available && !self.cpu_data.stale {
+                    let threshold = self.high_threshold_celsius();
+                    if self.incident_logger.crossed(self.cpu_data.temp, threshold) {
+                        self.capture_incident(threshold);
+                    }
+                }
+
+                if self.settings.log_processes && !self.logging_paused {
+                    self.log_processes();
+                } else {
+                    self.process_logger = None;
+                }
+                reconnect_task
             }
         }
     }
@@ -441,9 +1148,22 @@ impl App {
         let page = match self.current_screen {
             Screen::Main => self
                 .main_window
-                .view(&self.cpu_data)
+                .view(
+                    &self.cpu_data,
+                    &self.memory,
+                    &self.gpus,
+                    &self.drives,
+                    &self.motherboard,
+                    &self.network,
+                    &self.batteries,
+                    &self.psus,
+                )
                 .map(AppMessage::MainWindow),
             Screen::Plotter => self.plot_window.view().map(AppMessage::PlotWindow),
+            Screen::Processes => self
+                .process_window
+                .view(&self.processes, &self.incident_logger.incidents)
+                .map(AppMessage::ProcessWindow),
         };
         if self.show_settings_modal {
             self.settings.view(layout::with_header(page))
@@ -456,14 +1176,29 @@ impl App {
         // https://docs.iced.rs/iced/#passive-subscriptions
         Subscription::batch(vec![
             window::close_events().map(AppMessage::WindowClosed),
-            iced::time::every(Duration::from_secs_f32(self.settings.data_update_interval))
-                .map(|_| AppMessage::UpdateHardwareData),
+            iced::time::every(Duration::from_secs_f32(
+                self.settings.active_update_interval(self.on_battery()),
+            ))
+            .map(|_| AppMessage::UpdateHardwareData),
             tray_events_subscription(),
+            iced::time::every(Duration::from_secs(3600)).map(|_| AppMessage::CompactLogs),
             self.main_window.subscription().map(AppMessage::MainWindow),
         ])
     }
 }
 
+/// Connect to the hardware monitor in the background. Until then the app runs on
+/// sysinfo and the frequency counter, with temperature and power unavailable
+#[cfg(windows)]
+fn connect_hardware_monitor() -> Task<AppMessage> {
+    Task::future(async { AppMessage::HardwareMonitorConnected(connect_with_backoff().await) })
+}
+
+#[cfg(not(windows))]
+fn connect_hardware_monitor() -> Task<AppMessage> {
+    Task::none()
+}
+
 /// Subscription for tray menu events
 fn tray_events_subscription() -> Subscription<AppMessage> {
     use iced::futures::SinkExt;
@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Value an alert rule watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Metric {
+    Temperature,   // CPU package, always °C
+    Power,         // CPU package, W
+    Usage,         // CPU, %
+    Frequency,     // CPU, GHz
+    FanRpm,        // Slowest fan that is being driven, RPM
+    RailDeviation, // Voltage rail furthest from its nominal value, %
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Metric::Temperature => write!(f, "CPU temperature"),
+            Metric::Power => write!(f, "CPU power"),
+            Metric::Usage => write!(f, "CPU usage"),
+            Metric::Frequency => write!(f, "CPU frequency"),
+            Metric::FanRpm => write!(f, "Slowest fan"),
+            Metric::RailDeviation => write!(f, "Voltage rail deviation"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    Above,
+    AboveOrEqual,
+    Below,
+    BelowOrEqual,
+}
+
+impl Comparison {
+    pub fn matches(&self, value: f32, threshold: f32) -> bool {
+        match self {
+            Comparison::Above => value > threshold,
+            Comparison::AboveOrEqual => value >= threshold,
+            Comparison::Below => value < threshold,
+            Comparison::BelowOrEqual => value <= threshold,
+        }
+    }
+
+    fn is_upper_bound(&self) -> bool {
+        matches!(self, Comparison::Above | Comparison::AboveOrEqual)
+    }
+}
+
+/// Saved in the config file. Durations are in seconds to keep the TOML readable
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub enabled: bool,
+    // Desktop notification when the alert fires
+    #[serde(default = "default_notify")]
+    pub notify: bool,
+    pub metric: Metric,
+    pub comparison: Comparison,
+    pub threshold: f32,
+    // Firing alerts resolve once the value is back past this one (hysteresis).
+    // Defaults to `threshold`
+    #[serde(default)]
+    pub clear_threshold: Option<f32>,
+    // How long the condition has to hold before the alert fires
+    #[serde(default)]
+    pub sustain_secs: f32,
+    // How long a resolved alert stays quiet before it can fire again
+    #[serde(default)]
+    pub cooldown_secs: f32,
+}
+
+fn default_notify() -> bool {
+    true
+}
+
+impl AlertRule {
+    /// Thresholds have to be numbers and durations have to fit in a `Duration`
+    pub fn is_valid(&self) -> bool {
+        self.threshold.is_finite()
+            && self.clear_threshold.is_none_or(f32::is_finite)
+            && Duration::try_from_secs_f32(self.sustain_secs).is_ok()
+            && Duration::try_from_secs_f32(self.cooldown_secs).is_ok()
+    }
+
+    /// Valid and not shadowing one of the built-in rules
+    pub fn is_usable(&self) -> bool {
+        self.is_valid() && !BUILTIN_RULE_NAMES.contains(&self.name.as_str())
+    }
+
+    /// True once the value is back on the safe side of the clear threshold
+    fn cleared(&self, value: f32) -> bool {
+        let clear = self.clear_threshold.unwrap_or(self.threshold);
+        if self.comparison.is_upper_bound() {
+            value < clear
+        } else {
+            value > clear
+        }
+    }
+}
+
+/// Resolve the temperature alert this far (°C) below the high threshold
+const TEMP_ALERT_HYSTERESIS: f32 = 5.0;
+
+pub const TEMPERATURE_RULE_NAME: &str = "CPU temperature high";
+pub const FAN_RULE_NAME: &str = "Fan stopped";
+pub const RAIL_RULE_NAME: &str = "Voltage rail out of spec";
+const BUILTIN_RULE_NAMES: [&str; 3] = [TEMPERATURE_RULE_NAME, FAN_RULE_NAME, RAIL_RULE_NAME];
+
+/// Alert for the high temperature threshold in settings, always evaluated first
+pub fn temperature_rule(high_threshold: f32, notify: bool) -> AlertRule {
+    AlertRule {
+        name: TEMPERATURE_RULE_NAME.to_string(),
+        enabled: true,
+        notify,
+        metric: Metric::Temperature,
+        comparison: Comparison::AboveOrEqual,
+        threshold: high_threshold,
+        clear_threshold: Some(high_threshold - TEMP_ALERT_HYSTERESIS),
+        sustain_secs: 0.0,
+        cooldown_secs: 30.0,
+    }
+}
+
+/// Alerts for a fan that stopped while being driven and a rail outside the ATX
+/// tolerance (%). Short spin-downs and load transients don't fire
+pub fn motherboard_rules(rail_tolerance: f32) -> Vec<AlertRule> {
+    vec![
+        AlertRule {
+            name: FAN_RULE_NAME.to_string(),
+            enabled: true,
+            notify: true,
+            metric: Metric::FanRpm,
+            comparison: Comparison::Below,
+            threshold: 1.0,
+            clear_threshold: None,
+            sustain_secs: 5.0,
+            cooldown_secs: 300.0,
+        },
+        AlertRule {
+            name: RAIL_RULE_NAME.to_string(),
+            enabled: true,
+            notify: true,
+            metric: Metric::RailDeviation,
+            comparison: Comparison::Above,
+            threshold: rail_tolerance,
+            clear_threshold: None,
+            sustain_secs: 5.0,
+            cooldown_secs: 300.0,
+        },
+    ]
+}
+
+/// Rules from the config file that can be evaluated. Invalid rules and rules using a
+/// built-in rule's name are left out, the config keeps them
+pub fn user_rules(rules: &[AlertRule]) -> Vec<AlertRule> {
+    rules
+        .iter()
+        .filter(|rule| rule.is_usable())
+        .cloned()
+        .collect()
+}
+
+/// Extra rules for new configs, next to the temperature rule
+pub fn default_alert_rules() -> Vec<AlertRule> {
+    vec![AlertRule {
+        name: "CPU power high".to_string(),
+        enabled: false,
+        notify: true,
+        metric: Metric::Power,
+        comparison: Comparison::Above,
+        threshold: 150.0,
+        clear_threshold: Some(120.0),
+        sustain_secs: 10.0,
+        cooldown_secs: 300.0,
+    }]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertState {
+    Ok,
+    // Condition holds, waiting for the sustain duration
+    Pending { since: Instant },
+    Firing,
+    // Back to normal, can't fire again until the cooldown has passed
+    Resolved { at: Instant },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertEventKind {
+    Fired,
+    Resolved,
+}
+
+/// State change worth telling the user about
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertEvent {
+    pub rule: AlertRule,
+    pub kind: AlertEventKind,
+    pub value: f32,
+}
+
+/// Evaluates alert rules against each new set of readings
+#[derive(Debug, Clone)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    states: Vec<AlertState>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        let states = vec![AlertState::Ok; rules.len()];
+        Self { rules, states }
+    }
+
+    /// Replace the rules. Rules that keep their name keep their state
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        self.states = rules
+            .iter()
+            .map(|rule| self.state(&rule.name).unwrap_or(AlertState::Ok))
+            .collect();
+        self.rules = rules;
+    }
+
+    pub fn state(&self, name: &str) -> Option<AlertState> {
+        self.rules
+            .iter()
+            .position(|rule| rule.name == name)
+            .map(|i| self.states[i])
+    }
+
+    /// Rules that are currently firing
+    pub fn firing(&self) -> impl Iterator<Item = &AlertRule> {
+        self.rules
+            .iter()
+            .zip(&self.states)
+            .filter(|(_, state)| matches!(state, AlertState::Firing))
+            .map(|(rule, _)| rule)
+    }
+
+    /// Advance every enabled rule with the current value of its metric. Rules whose
+    /// metric has no value (sensor unavailable) keep their state
+    pub fn evaluate(
+        &mut self,
+        value_of: impl Fn(Metric) -> Option<f32>,
+        now: Instant,
+    ) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        for (rule, state) in self.rules.iter().zip(self.states.iter_mut()) {
+            if !rule.enabled {
+                *state = AlertState::Ok;
+                continue;
+            }
+            let Some(value) = value_of(rule.metric) else {
+                continue;
+            };
+
+            // Cooldown over, start from scratch on this same reading
+            if let AlertState::Resolved { at } = *state {
+                if now.duration_since(at) >= Duration::from_secs_f32(rule.cooldown_secs) {
+                    *state = AlertState::Ok;
+                }
+            }
+
+            let active = rule.comparison.matches(value, rule.threshold);
+            let sustain = Duration::from_secs_f32(rule.sustain_secs);
+            let next = match *state {
+                AlertState::Ok if active => AlertState::Pending { since: now },
+                AlertState::Pending { .. } if !active => AlertState::Ok,
+                AlertState::Firing if rule.cleared(value) => AlertState::Resolved { at: now },
+                other => other,
+            };
+            // Pending can fire right away when there's no sustain duration
+            let next = match next {
+                AlertState::Pending { since } if now.duration_since(since) >= sustain => {
+                    AlertState::Firing
+                }
+                other => other,
+            };
+
+            let kind = match (*state, next) {
+                (AlertState::Firing, AlertState::Resolved { .. }) => Some(AlertEventKind::Resolved),
+                (AlertState::Ok | AlertState::Pending { .. }, AlertState::Firing) => {
+                    Some(AlertEventKind::Fired)
+                }
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                events.push(AlertEvent {
+                    rule: rule.clone(),
+                    kind,
+                    value,
+                });
+            }
+            *state = next;
+        }
+        events
+    }
+}
@@ -1,80 +1,522 @@
 use anyhow::Result;
 use chrono::prelude::*;
 use csv::{Error, Writer, WriterBuilder};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
-use std::path::PathBuf;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::SystemTime;
+use std::{fmt, fs};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CsvCpuLogEntry {
     pub timestamp: String,
     pub temperature_unit: String,
-    pub temperature: f32,
+    // Empty while the sensor is unavailable or the last poll failed
+    pub temperature: Option<f32>,
     pub cpu_usage: f32,
-    pub power_draw: f32,
+    pub power_draw: Option<f32>,
+    // Per-socket values, comma separated in package order. Empty on single package
+    // sources that don't report packages and in files written before these columns existed
+    #[serde(default)]
+    pub package_temperatures: String,
+    #[serde(default)]
+    pub package_power_draws: String,
+    // Per-core or per-CCD temperatures, comma separated in core order
+    #[serde(default)]
+    pub core_temperatures: String,
+    // Per-GPU values, comma separated in GPU order
+    #[serde(default)]
+    pub gpu_temperatures: String,
+    #[serde(default)]
+    pub gpu_loads: String,
+    #[serde(default)]
+    pub gpu_power_draws: String,
+    #[serde(default)]
+    pub gpu_vram_used: String,
+    // Per-drive values, comma separated in drive order
+    #[serde(default)]
+    pub drive_temperatures: String,
+    #[serde(default)]
+    pub drive_used_space: String,
+    #[serde(default)]
+    pub drive_remaining_life: String,
+    // Motherboard fans and voltage rails, comma separated in sensor order
+    #[serde(default)]
+    pub fan_rpms: String,
+    #[serde(default)]
+    pub rail_voltages: String,
+    // RAM and swap in use (GB), DIMM temperatures comma separated in module order
+    #[serde(default)]
+    pub memory_used: f32,
+    #[serde(default)]
+    pub swap_used: f32,
+    #[serde(default)]
+    pub dimm_temperatures: String,
+    // Throughput summed over all interfaces (MB/s)
+    #[serde(default)]
+    pub network_rx_rate: f32,
+    #[serde(default)]
+    pub network_tx_rate: f32,
+    // Per-battery charge (%) and rate (W, negative while discharging), comma separated
+    #[serde(default)]
+    pub battery_charge_levels: String,
+    #[serde(default)]
+    pub battery_rates: String,
+    #[serde(default)]
+    pub on_battery: bool,
+    // Total wall draw of all PSUs (W), compare with power_draw for the CPU share.
+    // Per-PSU output power and efficiency, comma separated
+    #[serde(default)]
+    pub wall_power_draw: Option<f32>,
+    #[serde(default)]
+    pub psu_output_powers: String,
+    #[serde(default)]
+    pub psu_efficiencies: String,
+}
+
+/// Join per-device values into one column value. Missing values are left empty
+pub fn join_values(values: impl IntoIterator<Item = Option<f32>>) -> String {
+    values
+        .into_iter()
+        .map(|value| value.map(|v| format!("{:.1}", v)).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Per-device values of a joined column, see `join_values`. Empty and unparsable
+/// values are `None`
+pub fn split_values<T: FromStr>(joined: &str) -> Vec<Option<T>> {
+    if joined.is_empty() {
+        return Vec::new();
+    }
+    joined
+        .split(',')
+        .map(|value| value.trim().parse().ok())
+        .collect()
+}
+
+impl CsvCpuLogEntry {
+    /// Every column by name, formatted the way serde writes it. Schemas pick their
+    /// columns from this, columns this version doesn't know are left empty
+    pub fn fields(&self) -> Result<HashMap<String, String>, Error> {
+        let mut wtr = Writer::from_writer(Vec::new());
+        wtr.serialize(self)?;
+        let data = wtr.into_inner().map_err(|e| Error::from(e.into_error()))?;
+        let mut rdr = csv::ReaderBuilder::new().from_reader(data.as_slice());
+        let headers = rdr.headers()?.clone();
+        let record = rdr.records().next().transpose()?.unwrap_or_default();
+        Ok(headers
+            .iter()
+            .zip(record.iter())
+            .map(|(column, value)| (column.to_string(), value.to_string()))
+            .collect())
+    }
+}
+
+/// Group of CPU log columns that can be turned on and off in settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetricGroup {
+    Cpu, // Always logged, every schema starts with these columns
+    Packages,
+    Cores,
+    Gpu,
+    Drives,
+    Motherboard,
+    Memory,
+    Network,
+    Battery,
+    Psu,
+}
+
+impl MetricGroup {
+    pub const ALL: [MetricGroup; 10] = [
+        MetricGroup::Cpu,
+        MetricGroup::Packages,
+        MetricGroup::Cores,
+        MetricGroup::Gpu,
+        MetricGroup::Drives,
+        MetricGroup::Motherboard,
+        MetricGroup::Memory,
+        MetricGroup::Network,
+        MetricGroup::Battery,
+        MetricGroup::Psu,
+    ];
+
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            MetricGroup::Cpu => &[
+                "timestamp",
+                "temperature_unit",
+                "temperature",
+                "cpu_usage",
+                "power_draw",
+            ],
+            MetricGroup::Packages => &["package_temperatures", "package_power_draws"],
+            MetricGroup::Cores => &["core_temperatures"],
+            MetricGroup::Gpu => &[
+                "gpu_temperatures",
+                "gpu_loads",
+                "gpu_power_draws",
+                "gpu_vram_used",
+            ],
+            MetricGroup::Drives => &[
+                "drive_temperatures",
+                "drive_used_space",
+                "drive_remaining_life",
+            ],
+            MetricGroup::Motherboard => &["fan_rpms", "rail_voltages"],
+            MetricGroup::Memory => &["memory_used", "swap_used", "dimm_temperatures"],
+            MetricGroup::Network => &["network_rx_rate", "network_tx_rate"],
+            MetricGroup::Battery => &["battery_charge_levels", "battery_rates", "on_battery"],
+            MetricGroup::Psu => &["wall_power_draw", "psu_output_powers", "psu_efficiencies"],
+        }
+    }
 }
+
+impl fmt::Display for MetricGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetricGroup::Cpu => write!(f, "CPU"),
+            MetricGroup::Packages => write!(f, "CPU packages"),
+            MetricGroup::Cores => write!(f, "Core temperatures"),
+            MetricGroup::Gpu => write!(f, "GPU"),
+            MetricGroup::Drives => write!(f, "Drives"),
+            MetricGroup::Motherboard => write!(f, "Fans and voltages"),
+            MetricGroup::Memory => write!(f, "Memory"),
+            MetricGroup::Network => write!(f, "Network"),
+            MetricGroup::Battery => write!(f, "Battery"),
+            MetricGroup::Psu => write!(f, "PSU"),
+        }
+    }
+}
+
+/// Bump when the meaning of existing columns changes. Files written before
+/// versioning have no version line and count as version 1
+pub const CSV_SCHEMA_VERSION: u32 = 2;
+const SCHEMA_VERSION_PREFIX: &str = "#schema_version=";
+
+/// Columns of a CPU log file. Written as a "#schema_version=N" line followed by
+/// the header, so each file describes itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvSchema {
+    pub version: u32,
+    pub columns: Vec<String>,
+}
+
+impl CsvSchema {
+    /// Schema for the enabled metric groups, in `MetricGroup::ALL` order
+    pub fn new(groups: &[MetricGroup]) -> Self {
+        let columns = MetricGroup::ALL
+            .iter()
+            .filter(|group| **group == MetricGroup::Cpu || groups.contains(group))
+            .flat_map(|group| group.columns())
+            .map(|column| column.to_string())
+            .collect();
+        Self {
+            version: CSV_SCHEMA_VERSION,
+            columns,
+        }
+    }
+
+    /// Schema of an existing file, `None` if it doesn't exist or has no header yet
+    pub fn read(path: &Path) -> Result<Option<Self>, Error> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut version = 1;
+        for line in BufReader::new(open_log(path)?).lines() {
+            let line = line?;
+            if let Some(v) = line.strip_prefix(SCHEMA_VERSION_PREFIX) {
+                version = v.trim().parse().unwrap_or(version);
+            } else if !line.trim().is_empty() {
+                let columns = line.trim_end().split(';').map(str::to_string).collect();
+                return Ok(Some(Self { version, columns }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Default for CsvSchema {
+    fn default() -> Self {
+        Self::new(&MetricGroup::ALL)
+    }
+}
+
+/// Read a CPU log written with any schema version. Columns missing from older
+/// schemas or disabled in settings get their default value. Archived logs are
+/// decompressed, see `open_log`
+pub fn read_cpu_log(path: &Path) -> Result<Vec<CsvCpuLogEntry>> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(b';')
+        .comment(Some(b'#'))
+        .from_reader(open_log(path)?);
+    let mut result = vec![];
+    for data in rdr.deserialize() {
+        result.push(data?);
+    }
+    Ok(result)
+}
+
+/// Compression of finished daily logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl LogCompression {
+    pub const ALL: [LogCompression; 3] = [
+        LogCompression::None,
+        LogCompression::Gzip,
+        LogCompression::Zstd,
+    ];
+
+    fn extension(&self) -> Option<&'static str> {
+        match self {
+            LogCompression::None => None,
+            LogCompression::Gzip => Some("gz"),
+            LogCompression::Zstd => Some("zst"),
+        }
+    }
+
+    /// Compression of a log file, from its extension
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => LogCompression::Gzip,
+            Some("zst") => LogCompression::Zstd,
+            _ => LogCompression::None,
+        }
+    }
+
+    /// "<name>.csv.gz" for "<name>.csv"
+    pub fn archive_path(&self, path: &Path) -> PathBuf {
+        match self.extension() {
+            Some(extension) => {
+                let mut name = path.as_os_str().to_owned();
+                name.push(".");
+                name.push(extension);
+                PathBuf::from(name)
+            }
+            None => path.to_path_buf(),
+        }
+    }
+}
+
+impl fmt::Display for LogCompression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogCompression::None => write!(f, "Off"),
+            LogCompression::Gzip => write!(f, "gzip"),
+            LogCompression::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+/// Compress the plain CPU logs of every day before `today` in `dir`, including files
+/// continued after a schema change. Roll-ups are left as they are. Returns how many
+/// files were compressed. A file that can't be compressed doesn't stop the others,
+/// the first error is returned after all files were tried
+pub fn archive_finished_logs(
+    dir: &Path,
+    compression: LogCompression,
+    today: NaiveDate,
+) -> io::Result<usize> {
+    if compression == LogCompression::None || !dir.exists() {
+        return Ok(0);
+    }
+    let mut archived = 0;
+    let mut first_error = None;
+    for file in fs::read_dir(dir)? {
+        let path = file?.path();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        // "<date>_cpu_logs.csv" or "<date>_cpu_logs_<n>.csv"
+        let Some((date, rest)) = name.split_once("_cpu_logs") else {
+            continue;
+        };
+        let Some(suffix) = rest.strip_suffix(".csv") else {
+            continue;
+        };
+        let numbered = suffix
+            .strip_prefix('_')
+            .is_some_and(|n| n.parse::<u32>().is_ok());
+        if !suffix.is_empty() && !numbered {
+            continue;
+        }
+        let Ok(date) = NaiveDate::parse_from_str(date, "%d-%m-%Y") else {
+            continue;
+        };
+        if date < today {
+            match archive_log(&path, compression) {
+                Ok(_) => archived += 1,
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+    }
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(archived),
+    }
+}
+
+/// Compress a finished log next to it and remove the original. The archive is
+/// written under a temporary name first, so it's either complete or missing.
+/// An existing archive is never replaced, the plain file is left for the user
+pub fn archive_log(path: &Path, compression: LogCompression) -> io::Result<PathBuf> {
+    let archive = compression.archive_path(path);
+    if archive == path {
+        return Ok(archive);
+    }
+    if archive.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists, leaving {} uncompressed",
+                archive.display(),
+                path.display()
+            ),
+        ));
+    }
+    let mut tmp = archive.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut input = File::open(path)?;
+    let output = File::create(&tmp)?;
+    match compression {
+        LogCompression::Gzip => {
+            let mut encoder = GzEncoder::new(output, flate2::Compression::default());
+            io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?;
+        }
+        LogCompression::Zstd => {
+            let mut encoder = zstd::Encoder::new(output, 0)?;
+            io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?;
+        }
+        LogCompression::None => {}
+    }
+    fs::rename(&tmp, &archive)?;
+    fs::remove_file(path)?;
+    Ok(archive)
+}
+
+/// Open a log for reading, decompressing archives. A plain ".csv" path that has
+/// been archived since is found under its archive name
+pub fn open_log(path: &Path) -> io::Result<Box<dyn Read>> {
+    let path = if path.exists() {
+        path.to_path_buf()
+    } else {
+        [LogCompression::Gzip, LogCompression::Zstd]
+            .iter()
+            .map(|compression| compression.archive_path(path))
+            .find(|archive| archive.exists())
+            .unwrap_or_else(|| path.to_path_buf())
+    };
+    let file = File::open(&path)?;
+    Ok(match LogCompression::of(&path) {
+        LogCompression::None => Box::new(file),
+        LogCompression::Gzip => Box::new(MultiGzDecoder::new(BufReader::new(file))),
+        LogCompression::Zstd => Box::new(zstd::Decoder::new(file)?),
+    })
+}
+
 #[derive(Debug)]
 pub struct CsvLogger {
     wtr: Writer<File>,
+    pub dir: String,
+    schema: CsvSchema,
     pub path: PathBuf,
     pub timestamp: DateTime<Local>,
     pub runtime_start: SystemTime,
-    write_buffer_size: usize,
+    // Entries buffered before they're written to the file
+    pub write_buffer_size: usize,
     pub write_buffer: Vec<CsvCpuLogEntry>,
     pub graph_data_buffer: Vec<CsvCpuLogEntry>, // TODO: For upcoming line graph. THIS IS HERE FOR NOW
 }
 
 impl CsvLogger {
+    /// Logger with every metric group enabled
     pub fn new(custom_dir_path: Option<&str>) -> Result<Self> {
-        let dir = custom_dir_path.unwrap_or("logs");
-        fs::create_dir_all(dir)?;
-        let date_str = Local::now().format("%d-%m-%Y").to_string();
-        let path = PathBuf::from(format!("{}/{}_cpu_logs.csv", dir, date_str));
+        Self::with_schema(custom_dir_path, CsvSchema::default())
+    }
 
-        let wtr = Self::open_csv_writer(&path)?;
+    pub fn with_schema(custom_dir_path: Option<&str>, schema: CsvSchema) -> Result<Self> {
+        let dir = custom_dir_path.unwrap_or("logs").to_string();
+        fs::create_dir_all(&dir)?;
+        let timestamp = Local::now();
+        let path = Self::file_path(&dir, &timestamp, &schema)?;
+
+        let wtr = Self::open_csv_writer(&path, &schema)?;
 
         Ok(Self {
             wtr,
+            dir,
+            schema,
             path,
-            timestamp: Local::now(),
+            timestamp,
             runtime_start: SystemTime::now(),
-            write_buffer_size: 1, // TODO: Change back to 50 in prod. Make it configurable?
+            write_buffer_size: 1, // TODO: Change back to 50 in prod
             write_buffer: vec![],
             graph_data_buffer: vec![],
         })
     }
 
+    /// Switch to a new set of columns. Buffered entries are written with the old
+    /// schema first, later ones go to a file with a matching header
+    pub fn set_schema(&mut self, schema: CsvSchema) -> Result<(), Error> {
+        if schema == self.schema {
+            return Ok(());
+        }
+        self.flush_buffer()?;
+        self.path = Self::file_path(&self.dir, &self.timestamp, &schema)?;
+        self.wtr = Self::open_csv_writer(&self.path, &schema)?;
+        self.schema = schema;
+        Ok(())
+    }
+
     pub fn update_path(&mut self, new_path: PathBuf) {
         self.path = new_path;
-        self.wtr = Self::open_csv_writer(&self.path).unwrap();
+        self.wtr = Self::open_csv_writer(&self.path, &self.schema).unwrap();
     }
     pub fn read(&self) -> Result<Vec<CsvCpuLogEntry>> {
-        let mut rdr = csv::ReaderBuilder::new()
-            .delimiter(b';')
-            .from_path(&self.path)?;
-        let mut result = vec![];
-        for data in rdr.deserialize() {
-            let record: CsvCpuLogEntry = data?;
+        let result = read_cpu_log(&self.path)?;
+        for record in &result {
             println!("{:?}", record);
-            result.push(record);
         }
         Ok(result)
     }
-    pub fn write(&mut self, mut entries: Vec<CsvCpuLogEntry>) -> Result<(), Error> {
+    pub fn write(&mut self, entries: Vec<CsvCpuLogEntry>) -> Result<(), Error> {
+        self.write_at(entries, Local::now())
+    }
+
+    /// Write as if it's `today`, the log rotates to a new file when the day changes
+    pub fn write_at(
+        &mut self,
+        mut entries: Vec<CsvCpuLogEntry>,
+        today: DateTime<Local>,
+    ) -> Result<(), Error> {
         // Check current day if new writer with updated path is needed
-        let today = Local::now();
         let date_str = today.format("%d-%m-%Y").to_string();
 
         if date_str != self.timestamp.format("%d-%m-%Y").to_string() {
-            // Flush pending writes before rotating to new file
-            self.flush_buffer()?;
-
+            // Rotate before flushing, the finished day may already be archived and
+            // flushing to it would start a new file for that day
             self.timestamp = today;
-            let new_filename = format!("logs/{}_cpu_logs.csv", date_str);
-            self.path = PathBuf::from(&new_filename);
-            self.wtr = Self::open_csv_writer(&self.path)?;
+            self.path = Self::file_path(&self.dir, &self.timestamp, &self.schema)?;
+            self.wtr = Self::open_csv_writer(&self.path, &self.schema)?;
+            self.flush_buffer()?;
         }
 
         // Add to graph data (last 1000 for now)
@@ -95,54 +537,162 @@ impl CsvLogger {
     }
 
     pub fn flush_buffer(&mut self) -> Result<(), Error> {
+        // Nothing to write, don't recreate a file that was archived or deleted
+        if self.write_buffer.is_empty() {
+            return Ok(());
+        }
         // Check if file still exists, recreate if deleted
         if !self.path.exists() {
             eprintln!("CSV file was deleted, recreating: {:?}", self.path);
             // Ensure parent directory exists
             if let Some(parent) = self.path.parent() {
                 fs::create_dir_all(parent).map_err(|e| {
-                    Error::from(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("Failed to create directory: {}", e),
-                    ))
+                    Error::from(std::io::Error::other(format!(
+                        "Failed to create directory: {}",
+                        e
+                    )))
                 })?;
             }
 
             // Recreate the writer in append mode with headers
-            self.wtr = Self::open_csv_writer(&self.path)?;
+            self.wtr = Self::open_csv_writer(&self.path, &self.schema)?;
         }
 
         for entry in &self.write_buffer {
-            self.wtr.serialize(entry)?;
+            let fields = entry.fields()?;
+            self.wtr.write_record(
+                self.schema
+                    .columns
+                    .iter()
+                    .map(|c| fields.get(c).map(String::as_str).unwrap_or_default()),
+            )?;
         }
         self.wtr.flush()?;
         self.write_buffer.clear(); // Clear after writing to avoid duplicates
         Ok(())
     }
 
-    // Helper function to open CSV writer in append mode with header check
-    fn open_csv_writer(path: &PathBuf) -> Result<Writer<File>, Error> {
-        let file_exists = path.exists();
+    /// "<date>_cpu_logs.csv", or the first "<date>_cpu_logs_<n>.csv" that is new or
+    /// has the same schema when an earlier file of the day has different columns
+    fn file_path(dir: &str, date: &DateTime<Local>, schema: &CsvSchema) -> Result<PathBuf, Error> {
+        let date_str = date.format("%d-%m-%Y");
+        let mut path = PathBuf::from(format!("{}/{}_cpu_logs.csv", dir, date_str));
+        let mut suffix = 1;
+        while let Some(existing) = CsvSchema::read(&path)? {
+            if existing == *schema {
+                break;
+            }
+            suffix += 1;
+            path = PathBuf::from(format!("{}/{}_cpu_logs_{}.csv", dir, date_str, suffix));
+        }
+        Ok(path)
+    }
 
-        let file = OpenOptions::new().create(true).append(true).open(path)?;
+    // Helper function to open CSV writer in append mode, new files get the schema header
+    fn open_csv_writer(path: &Path, schema: &CsvSchema) -> Result<Writer<File>, Error> {
+        let has_header = CsvSchema::read(path)?.is_some();
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if !has_header {
+            writeln!(file, "{}{}", SCHEMA_VERSION_PREFIX, schema.version)?;
+        }
 
         let mut wtr = WriterBuilder::new()
             .delimiter(b';')
-            .has_headers(!file_exists)
+            .has_headers(false)
             .from_writer(file);
 
         // Write headers if new file
-        if !file_exists {
-            wtr.write_record(&[
-                "timestamp",
-                "temperature_unit",
-                "temperature",
-                "cpu_usage",
-                "power_draw",
-            ])?;
+        if !has_header {
+            wtr.write_record(&schema.columns)?;
             wtr.flush()?;
         }
 
         Ok(wtr)
     }
 }
+
+/// One process of a tick's top processes, see `ProcessCsvLogger`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvProcessLogEntry {
+    pub timestamp: String,
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32,
+    pub memory: f32, // MB
+    // Estimated share of the package power (W), empty without a power sensor
+    pub power_draw: String,
+}
+
+/// Optional log of the top processes, written next to the CPU log as
+/// "<date>_process_logs.csv". Rows are written right away, there's no graph buffer
+#[derive(Debug)]
+pub struct ProcessCsvLogger {
+    wtr: Writer<File>,
+    dir: String,
+    pub path: PathBuf,
+    pub timestamp: DateTime<Local>,
+}
+
+impl ProcessCsvLogger {
+    pub fn new(custom_dir_path: Option<&str>) -> Result<Self> {
+        let dir = custom_dir_path.unwrap_or("logs").to_string();
+        fs::create_dir_all(&dir)?;
+        let timestamp = Local::now();
+        let path = Self::file_path(&dir, &timestamp);
+        let wtr = Self::open_csv_writer(&path)?;
+
+        Ok(Self {
+            wtr,
+            dir,
+            path,
+            timestamp,
+        })
+    }
+
+    pub fn read(&self) -> Result<Vec<CsvProcessLogEntry>> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_path(&self.path)?;
+        let mut result = vec![];
+        for data in rdr.deserialize() {
+            result.push(data?);
+        }
+        Ok(result)
+    }
+
+    pub fn write(&mut self, entries: &[CsvProcessLogEntry]) -> Result<(), Error> {
+        // New file every day, same as the CPU log
+        let today = Local::now();
+        if today.date_naive() != self.timestamp.date_naive() || !self.path.exists() {
+            self.timestamp = today;
+            self.path = Self::file_path(&self.dir, &today);
+            fs::create_dir_all(&self.dir)?;
+            self.wtr = Self::open_csv_writer(&self.path)?;
+        }
+
+        for entry in entries {
+            self.wtr.serialize(entry)?;
+        }
+        self.wtr.flush()?;
+        Ok(())
+    }
+
+    fn file_path(dir: &str, date: &DateTime<Local>) -> PathBuf {
+        PathBuf::from(format!(
+            "{}/{}_process_logs.csv",
+            dir,
+            date.format("%d-%m-%Y")
+        ))
+    }
+
+    // Headers come from the entry fields, only written to new files
+    fn open_csv_writer(path: &PathBuf) -> Result<Writer<File>, Error> {
+        let file_exists = path.exists();
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(WriterBuilder::new()
+            .delimiter(b';')
+            .has_headers(!file_exists)
+            .from_writer(file))
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use csv::{Error, WriterBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+
+// Degrees below the threshold before another incident can be captured, so readings
+// hovering around the threshold don't log an incident every poll
+const REARM_MARGIN: f32 = 5.0;
+
+/// Snapshot taken when the CPU temperature crosses the high threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Incident {
+    pub timestamp: String,
+    pub temperature: f32, // °C
+    pub threshold: f32,   // °C
+    pub frequency: f64,   // GHz
+    // Per-core usage (%), comma separated in core order
+    pub core_usage: String,
+    // Busiest processes as "name (pid) usage%", comma separated, busiest first
+    pub top_processes: String,
+}
+
+/// Detects high temperature threshold crossings and keeps every incident in
+/// "incidents.csv" in the logs directory
+#[derive(Debug)]
+pub struct IncidentLogger {
+    pub path: PathBuf,
+    above_threshold: bool,
+    pub incidents: Vec<Incident>, // Oldest first
+}
+
+impl IncidentLogger {
+    /// Open the incident log, loading incidents recorded by earlier runs. Rows that
+    /// can't be read are skipped, the log is still appended to
+    pub fn new(custom_dir_path: Option<&str>) -> Self {
+        let dir = custom_dir_path.unwrap_or("logs");
+        let path = PathBuf::from(format!("{}/incidents.csv", dir));
+
+        let mut incidents = vec![];
+        if path.exists() {
+            match csv::ReaderBuilder::new().delimiter(b';').from_path(&path) {
+                Ok(mut rdr) => {
+                    for data in rdr.deserialize() {
+                        match data {
+                            Ok(incident) => incidents.push(incident),
+                            Err(e) => eprintln!("Skipping unreadable incident: {}", e),
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Failed to read incident log: {}", e),
+            }
+        }
+
+        Self {
+            path,
+            above_threshold: false,
+            incidents,
+        }
+    }
+
+    /// True on the first reading at or above the threshold. Re-arms once the
+    /// temperature drops REARM_MARGIN below it
+    pub fn crossed(&mut self, temperature: f32, threshold: f32) -> bool {
+        let was_above = self.above_threshold;
+        self.above_threshold = if was_above {
+            temperature >= threshold - REARM_MARGIN
+        } else {
+            temperature >= threshold
+        };
+        self.above_threshold && !was_above
+    }
+
+    pub fn record(&mut self, incident: Incident) -> Result<(), Error> {
+        let file_exists = self.path.exists();
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut wtr = WriterBuilder::new()
+            .delimiter(b';')
+            .has_headers(!file_exists)
+            .from_writer(file);
+        wtr.serialize(&incident)?;
+        wtr.flush()?;
+
+        self.incidents.push(incident);
+        Ok(())
+    }
+}
@@ -1 +1,6 @@
+pub mod alert_engine;
 pub mod csv_logger;
+pub mod incident_logger;
+pub mod notifications;
+pub mod retention;
+pub mod sqlite_store;
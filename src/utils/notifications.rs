@@ -0,0 +1,117 @@
+use super::alert_engine::{AlertEvent, AlertEventKind, Metric};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Urgency {
+    Normal,
+    Critical,
+}
+
+/// A desktop notification, independent of how it's delivered
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    pub urgency: Urgency,
+}
+
+/// Where notifications go (D-Bus on Linux, a mock in tests)
+pub trait NotificationSink {
+    fn notify(&mut self, notification: &Notification) -> Result<(), String>;
+}
+
+impl Notification {
+    /// Notification for a fired alert, `None` for resolved alerts and rules with
+    /// notifications turned off
+    pub fn for_alert(event: &AlertEvent) -> Option<Self> {
+        if event.kind != AlertEventKind::Fired || !event.rule.notify {
+            return None;
+        }
+        let unit = match event.rule.metric {
+            Metric::Temperature => "°C",
+            Metric::Power => " W",
+            Metric::Usage => "%",
+            Metric::Frequency => " GHz",
+            Metric::FanRpm => " RPM",
+            Metric::RailDeviation => "%",
+        };
+        Some(Self {
+            summary: event.rule.name.clone(),
+            body: format!(
+                "{} is {:.1}{} (threshold {:.1}{})",
+                event.rule.metric, event.value, unit, event.rule.threshold, unit
+            ),
+            urgency: if event.rule.metric == Metric::Temperature {
+                Urgency::Critical
+            } else {
+                Urgency::Normal
+            },
+        })
+    }
+}
+
+/// Send a notification for every fired alert that has them enabled.
+/// Returns the delivery errors, a failed notification doesn't stop the others
+pub fn notify_alerts(sink: &mut dyn NotificationSink, events: &[AlertEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(Notification::for_alert)
+        .filter_map(|notification| sink.notify(&notification).err())
+        .collect()
+}
+
+// Notifications are sent from the UI update, a hung notification daemon must not
+// freeze the app
+#[cfg(target_os = "linux")]
+const NOTIFY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// freedesktop.org notifications over the session D-Bus
+#[cfg(target_os = "linux")]
+pub struct DbusNotifier {
+    connection: zbus::blocking::Connection,
+}
+
+#[cfg(target_os = "linux")]
+impl DbusNotifier {
+    pub fn new() -> zbus::Result<Self> {
+        Ok(Self {
+            connection: zbus::blocking::connection::Builder::session()?
+                .method_timeout(NOTIFY_TIMEOUT)
+                .build()?,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl NotificationSink for DbusNotifier {
+    fn notify(&mut self, notification: &Notification) -> Result<(), String> {
+        use std::collections::HashMap;
+        use zbus::zvariant::Value;
+
+        // Urgency levels from the spec: 0 low, 1 normal, 2 critical
+        let urgency: u8 = match notification.urgency {
+            Urgency::Normal => 1,
+            Urgency::Critical => 2,
+        };
+        let hints: HashMap<&str, Value> = HashMap::from([("urgency", Value::U8(urgency))]);
+        let actions: Vec<&str> = Vec::new();
+        self.connection
+            .call_method(
+                Some("org.freedesktop.Notifications"),
+                "/org/freedesktop/Notifications",
+                Some("org.freedesktop.Notifications"),
+                "Notify",
+                &(
+                    "TempMon",
+                    0u32, // Don't replace an earlier notification
+                    "",
+                    notification.summary.as_str(),
+                    notification.body.as_str(),
+                    actions,
+                    hints,
+                    -1i32, // Server default timeout
+                ),
+            )
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
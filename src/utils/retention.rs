@@ -0,0 +1,437 @@
+use super::csv_logger::{read_cpu_log, split_values, CsvCpuLogEntry, CsvSchema};
+use anyhow::{bail, Result};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone};
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Columns that are kept as they are instead of rolled up
+const NOT_ROLLED_UP: [&str; 3] = ["timestamp", "temperature_unit", "on_battery"];
+
+/// How long each resolution of the CPU log is kept, in days. Today's log is never
+/// touched, older days are rolled up to 1-minute and then 1-hour min/avg/max
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub enabled: bool,
+    pub raw_days: u32,
+    pub minute_days: u32,
+    pub delete_after_days: u32,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        // Off by default, deleting logs is opt-in
+        Self {
+            enabled: false,
+            raw_days: 7,
+            minute_days: 30,
+            delete_after_days: 365,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Each stage has to keep data at least as long as the one before it
+    pub fn is_valid(&self) -> bool {
+        self.raw_days >= 1
+            && self.raw_days <= self.minute_days
+            && self.minute_days <= self.delete_after_days
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Minute,
+    Hour,
+}
+
+impl Resolution {
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Resolution::Minute => 60,
+            Resolution::Hour => 3600,
+        }
+    }
+
+    /// File name suffix, "<date>_cpu_logs_1m.csv"
+    fn suffix(&self) -> &'static str {
+        match self {
+            Resolution::Minute => "1m",
+            Resolution::Hour => "1h",
+        }
+    }
+}
+
+/// Min, max and average of the samples of one value in a bucket
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: f32,
+    pub max: f32,
+    pub sum: f64,
+    pub count: u32,
+}
+
+impl Stats {
+    pub fn new(value: f32) -> Self {
+        Self {
+            min: value,
+            max: value,
+            sum: value as f64,
+            count: 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: &Stats) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sum += other.sum;
+        self.count += other.count;
+    }
+
+    pub fn avg(&self) -> f32 {
+        (self.sum / self.count.max(1) as f64) as f32
+    }
+}
+
+/// Aggregated samples starting at `timestamp`. Each column has one `Stats` per
+/// device, scalar columns have a single one. A raw sample is a bucket of one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub timestamp: DateTime<FixedOffset>,
+    pub temperature_unit: String,
+    pub samples: u32,
+    pub columns: BTreeMap<String, Vec<Option<Stats>>>,
+}
+
+impl Bucket {
+    /// Bucket of one logged sample, only the given schema columns are used since
+    /// the others read as defaults. `None` when the timestamp can't be parsed
+    pub fn from_entry(entry: &CsvCpuLogEntry, columns: &[String]) -> Option<Self> {
+        let timestamp = parse_timestamp(&entry.timestamp)?;
+        let fields = entry.fields().ok()?;
+        let columns = columns
+            .iter()
+            .filter(|column| !NOT_ROLLED_UP.contains(&column.as_str()))
+            .map(|column| {
+                let values = split_values(fields.get(column).map_or("", String::as_str))
+                    .into_iter()
+                    .map(|value| value.map(Stats::new))
+                    .collect();
+                (column.clone(), values)
+            })
+            .collect();
+        Some(Self {
+            timestamp,
+            temperature_unit: entry.temperature_unit.clone(),
+            samples: 1,
+            columns,
+        })
+    }
+
+    fn merge(&mut self, other: &Bucket) {
+        self.samples += other.samples;
+        for (column, values) in &other.columns {
+            let merged = self.columns.entry(column.clone()).or_default();
+            if merged.len() < values.len() {
+                merged.resize(values.len(), None);
+            }
+            for (merged, value) in merged.iter_mut().zip(values) {
+                match (merged.as_mut(), value) {
+                    (Some(merged), Some(value)) => merged.merge(value),
+                    (None, Some(value)) => *merged = Some(*value),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Merge buckets into buckets of `resolution`, aligned to local time and oldest
+/// first. Samples logged in different temperature units are kept apart
+pub fn roll_up(buckets: impl IntoIterator<Item = Bucket>, resolution: Resolution) -> Vec<Bucket> {
+    let mut rolled: BTreeMap<(i64, String), Bucket> = BTreeMap::new();
+    for mut bucket in buckets {
+        let offset = bucket.timestamp.offset().local_minus_utc() as i64;
+        let local = bucket.timestamp.timestamp() + offset;
+        let start = local - local.rem_euclid(resolution.seconds()) - offset;
+        let key = (start, bucket.temperature_unit.clone());
+        match rolled.get_mut(&key) {
+            Some(existing) => existing.merge(&bucket),
+            None => {
+                bucket.timestamp = bucket
+                    .timestamp
+                    .offset()
+                    .timestamp_opt(start, 0)
+                    .single()
+                    .unwrap_or(bucket.timestamp);
+                rolled.insert(key, bucket);
+            }
+        }
+    }
+    rolled.into_values().collect()
+}
+
+/// Write rolled up buckets as "timestamp;temperature_unit;samples;<column>_min;
+/// <column>_avg;<column>_max;...", per-device values comma separated
+pub fn write_rollup(path: &Path, buckets: &[Bucket]) -> Result<()> {
+    let mut columns: Vec<&String> = buckets.iter().flat_map(|b| b.columns.keys()).collect();
+    columns.sort();
+    columns.dedup();
+
+    let mut wtr = WriterBuilder::new().delimiter(b';').from_path(path)?;
+    let mut header = vec![
+        "timestamp".to_string(),
+        "temperature_unit".to_string(),
+        "samples".to_string(),
+    ];
+    // Devices can be missing from some samples, each keeps its own count
+    for column in &columns {
+        for stat in ["min", "avg", "max", "count"] {
+            header.push(format!("{}_{}", column, stat));
+        }
+    }
+    wtr.write_record(&header)?;
+
+    for bucket in buckets {
+        let mut record = vec![
+            bucket.timestamp.to_rfc3339(),
+            bucket.temperature_unit.clone(),
+            bucket.samples.to_string(),
+        ];
+        for column in &columns {
+            let values = bucket.columns.get(*column).map_or(&[][..], Vec::as_slice);
+            record.push(join_stats(values, |s| s.min));
+            record.push(join_stats(values, |s| s.avg()));
+            record.push(join_stats(values, |s| s.max));
+            record.push(join_stats(values, |s| s.count));
+        }
+        wtr.write_record(&record)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Read a file written by `write_rollup`. Averages are weighted by their sample
+/// count when merged again. Files without count columns use the bucket's samples
+pub fn read_rollup(path: &Path) -> Result<Vec<Bucket>> {
+    let mut rdr = ReaderBuilder::new().delimiter(b';').from_path(path)?;
+    let header = rdr.headers()?.clone();
+    let columns: Vec<(String, usize, bool)> = header
+        .iter()
+        .enumerate()
+        .filter_map(|(i, name)| {
+            let column = name.strip_suffix("_min")?;
+            let has_count = header.get(i + 3) == Some(format!("{}_count", column).as_str());
+            Some((column.to_string(), i, has_count))
+        })
+        .collect();
+
+    let mut buckets = Vec::new();
+    for record in rdr.records() {
+        let record = record?;
+        let Some(timestamp) = record.get(0).and_then(parse_timestamp) else {
+            continue;
+        };
+        let samples: u32 = record.get(2).and_then(|s| s.parse().ok()).unwrap_or(1);
+        let columns = columns
+            .iter()
+            .map(|(column, i, has_count)| {
+                let counts = has_count.then(|| split_values(record.get(i + 3).unwrap_or_default()));
+                (column.clone(), read_stats(&record, *i, counts, samples))
+            })
+            .collect();
+        buckets.push(Bucket {
+            timestamp,
+            temperature_unit: record.get(1).unwrap_or_default().to_string(),
+            samples,
+            columns,
+        });
+    }
+    Ok(buckets)
+}
+
+/// What a compaction run did
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompactionReport {
+    pub rolled_up: usize,
+    pub deleted: usize,
+    pub compressed: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogKind {
+    Raw,
+    Rollup(Resolution),
+}
+
+/// Apply the retention policy to the CPU logs in `dir`. Rolled up files are written
+/// to a temporary file first, so a finished file means its sources can go
+pub fn compact_logs(
+    dir: &Path,
+    policy: &RetentionPolicy,
+    today: NaiveDate,
+) -> Result<CompactionReport> {
+    if !policy.is_valid() {
+        bail!("Invalid retention policy {:?}", policy);
+    }
+    let mut report = CompactionReport::default();
+    if !dir.exists() {
+        return Ok(report);
+    }
+
+    let mut days: BTreeMap<NaiveDate, Vec<(PathBuf, LogKind)>> = BTreeMap::new();
+    for file in fs::read_dir(dir)? {
+        let path = file?.path();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if let Some((date, kind)) = parse_log_name(name) {
+            days.entry(date).or_default().push((path, kind));
+        }
+    }
+
+    for (date, files) in days {
+        let age = (today - date).num_days();
+        let target = if age > policy.delete_after_days as i64 {
+            for (path, _) in &files {
+                fs::remove_file(path)?;
+                report.deleted += 1;
+            }
+            continue;
+        } else if age > policy.minute_days as i64 {
+            Resolution::Hour
+        } else if age > policy.raw_days as i64 {
+            Resolution::Minute
+        } else {
+            continue;
+        };
+
+        // Finer files than the target, a minute roll-up never replaces an hourly one
+        let sources: Vec<&(PathBuf, LogKind)> = files
+            .iter()
+            .filter(|(_, kind)| match kind {
+                LogKind::Raw => true,
+                LogKind::Rollup(resolution) => resolution.seconds() < target.seconds(),
+            })
+            .collect();
+        if sources.is_empty() {
+            continue;
+        }
+
+        let target_path = dir.join(format!(
+            "{}_cpu_logs_{}.csv",
+            date.format("%d-%m-%Y"),
+            target.suffix()
+        ));
+        if !target_path.exists() {
+            let mut buckets = Vec::new();
+            for (path, kind) in &sources {
+                match kind {
+                    LogKind::Raw => buckets.extend(read_raw_buckets(path)?),
+                    LogKind::Rollup(_) => buckets.extend(read_rollup(path)?),
+                }
+            }
+            let buckets = roll_up(buckets, target);
+            let tmp_path = target_path.with_extension("csv.tmp");
+            write_rollup(&tmp_path, &buckets)?;
+            fs::rename(&tmp_path, &target_path)?;
+        }
+        for (path, _) in sources {
+            fs::remove_file(path)?;
+            report.rolled_up += 1;
+        }
+    }
+    Ok(report)
+}
+
+/// Samples of a raw CPU log as one-sample buckets
+fn read_raw_buckets(path: &Path) -> Result<Vec<Bucket>> {
+    let Some(schema) = CsvSchema::read(path)? else {
+        return Ok(Vec::new());
+    };
+    Ok(read_cpu_log(path)?
+        .iter()
+        .filter_map(|entry| Bucket::from_entry(entry, &schema.columns))
+        .collect())
+}
+
+/// Date and kind of "<dd-mm-yyyy>_cpu_logs[_N|_1m|_1h].csv", raw logs may also be
+/// compressed archives
+fn parse_log_name(name: &str) -> Option<(NaiveDate, LogKind)> {
+    let archived = [".gz", ".zst"]
+        .iter()
+        .find_map(|extension| name.strip_suffix(extension));
+    let (date, rest) = archived
+        .unwrap_or(name)
+        .strip_suffix(".csv")?
+        .split_once("_cpu_logs")?;
+    let date = NaiveDate::parse_from_str(date, "%d-%m-%Y").ok()?;
+    let kind = match rest {
+        "" => LogKind::Raw,
+        "_1m" => LogKind::Rollup(Resolution::Minute),
+        "_1h" => LogKind::Rollup(Resolution::Hour),
+        // Files continued after a schema change
+        suffix if suffix.len() > 1 && suffix[1..].parse::<u32>().is_ok() => LogKind::Raw,
+        _ => return None,
+    };
+    if archived.is_some() && kind != LogKind::Raw {
+        return None;
+    }
+    Some((date, kind))
+}
+
+/// RFC 3339 as written by the app, local "YYYY-MM-DD HH:MM:SS" in older logs
+fn parse_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value).ok().or_else(|| {
+        let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").ok()?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|time| time.fixed_offset())
+    })
+}
+
+fn join_stats<T: ToString>(values: &[Option<Stats>], stat: impl Fn(&Stats) -> T) -> String {
+    values
+        .iter()
+        .map(|value| {
+            value
+                .as_ref()
+                .map(|s| stat(s).to_string())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// min, avg and max are in consecutive columns starting at `min_index`. Without
+// per-device counts every device is counted with all samples of the bucket
+fn read_stats(
+    record: &StringRecord,
+    min_index: usize,
+    counts: Option<Vec<Option<u32>>>,
+    samples: u32,
+) -> Vec<Option<Stats>> {
+    let column = |offset| split_values::<f32>(record.get(min_index + offset).unwrap_or_default());
+    let (min, avg, max) = (column(0), column(1), column(2));
+    min.iter()
+        .zip(&avg)
+        .zip(&max)
+        .enumerate()
+        .map(|(device, ((min, avg), max))| {
+            let count = match &counts {
+                Some(counts) => counts.get(device).copied().flatten()?,
+                None => samples,
+            };
+            Some(Stats {
+                min: (*min)?,
+                max: (*max)?,
+                sum: (*avg)? as f64 * count as f64,
+                count,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,467 @@
+use super::csv_logger::{split_values, CsvCpuLogEntry};
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection, OptionalExtension, ToSql};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Bump with a migration when the tables change. Version 2 added the package, core,
+/// fan, voltage, DIMM and PSU tables, which are created on open like the others.
+/// Version 3 stores the device name instead of its position in the logged column
+const SCHEMA_VERSION: i32 = 3;
+
+/// One table per metric family. Every table has an indexed `timestamp` column
+/// (Unix milliseconds), per-device families also have a `device` name column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    Cpu,
+    Package,
+    Core,
+    Memory,
+    Dimm,
+    Network,
+    Gpu,
+    Drive,
+    Fan,
+    Voltage,
+    Battery,
+    Psu,
+}
+
+impl Family {
+    pub const ALL: [Family; 12] = [
+        Family::Cpu,
+        Family::Package,
+        Family::Core,
+        Family::Memory,
+        Family::Dimm,
+        Family::Network,
+        Family::Gpu,
+        Family::Drive,
+        Family::Fan,
+        Family::Voltage,
+        Family::Battery,
+        Family::Psu,
+    ];
+
+    pub fn table(&self) -> &'static str {
+        match self {
+            Family::Cpu => "cpu",
+            Family::Package => "package",
+            Family::Core => "core",
+            Family::Memory => "memory",
+            Family::Dimm => "dimm",
+            Family::Network => "network",
+            Family::Gpu => "gpu",
+            Family::Drive => "drive",
+            Family::Fan => "fan",
+            Family::Voltage => "voltage",
+            Family::Battery => "battery",
+            Family::Psu => "psu",
+        }
+    }
+
+    /// Value columns, all REAL
+    pub fn columns(&self) -> &'static [&'static str] {
+        match self {
+            Family::Cpu => &["temperature", "usage", "power_draw"],
+            Family::Package => &["temperature", "power_draw"],
+            Family::Core => &["temperature"],
+            Family::Memory => &["used", "swap_used"],
+            Family::Dimm => &["temperature"],
+            Family::Network => &["rx_rate", "tx_rate"],
+            Family::Gpu => &["temperature", "load", "power_draw", "vram_used"],
+            Family::Drive => &["temperature", "used_space", "remaining_life"],
+            Family::Fan => &["rpm"],
+            Family::Voltage => &["volts"],
+            Family::Battery => &["charge_level", "rate"],
+            Family::Psu => &["output_power", "efficiency"],
+        }
+    }
+
+    pub fn per_device(&self) -> bool {
+        !matches!(self, Family::Cpu | Family::Memory | Family::Network)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Min,
+    Avg,
+    Max,
+}
+
+impl Aggregate {
+    fn sql(&self) -> &'static str {
+        match self {
+            Aggregate::Min => "MIN",
+            Aggregate::Avg => "AVG",
+            Aggregate::Max => "MAX",
+        }
+    }
+}
+
+/// A column of a family, optionally limited to one device
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub family: Family,
+    pub column: &'static str,
+    pub device: Option<String>,
+}
+
+impl Series {
+    pub fn new(family: Family, column: &'static str) -> Self {
+        Self {
+            family,
+            column,
+            device: None,
+        }
+    }
+
+    /// Only this device of a per-device family, by its name in `DeviceNames`
+    pub fn device(mut self, device: impl Into<String>) -> Self {
+        self.device = Some(device.into());
+        self
+    }
+
+    /// Column names end up in the SQL, only known ones are allowed
+    fn check(&self) -> Result<()> {
+        if !self.family.columns().contains(&self.column) {
+            bail!("Unknown column {} in {}", self.column, self.family.table());
+        }
+        if self.device.is_some() && !self.family.per_device() {
+            bail!("{} has no devices", self.family.table());
+        }
+        Ok(())
+    }
+
+    // WHERE clause shared by all queries, ?1 and ?2 are the time range, ?3 the device
+    fn filter(&self) -> String {
+        let device = if self.device.is_some() {
+            " AND device = ?3"
+        } else {
+            ""
+        };
+        format!(
+            "FROM {} WHERE timestamp BETWEEN ?1 AND ?2{} AND {} IS NOT NULL",
+            self.family.table(),
+            device,
+            self.column
+        )
+    }
+}
+
+/// Names of the devices in the joined columns of an entry, in column order. Rows are
+/// stored under the name so a device keeps its history when others come and go.
+/// Values without a name are stored as "#N", their position in the column
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceNames {
+    pub packages: Vec<String>,
+    pub cores: Vec<String>,
+    pub dimms: Vec<String>,
+    pub gpus: Vec<String>,
+    pub drives: Vec<String>,
+    pub fans: Vec<String>,
+    pub voltages: Vec<String>,
+    pub batteries: Vec<String>,
+    pub psus: Vec<String>,
+}
+
+/// Device ids of one family: the names, with a "#N" suffix on repeated names
+/// (two identical GPUs) and "#N" for values past the end of the names
+fn device_ids(names: &[String], count: usize) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::with_capacity(count);
+    for position in 0..count {
+        let id = match names.get(position) {
+            Some(name) if ids.contains(name) => format!("{} #{}", name, position),
+            Some(name) => name.clone(),
+            None => format!("#{}", position),
+        };
+        ids.push(id);
+    }
+    ids
+}
+
+/// Value at a point in time, or the aggregate of a bucket starting there
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub timestamp: i64, // Unix milliseconds
+    pub value: f64,
+}
+
+/// Optional embedded history next to the CSV log, queryable across days.
+/// Stored in "history.db" in the logs directory
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Connection,
+    pub path: PathBuf,
+}
+
+impl SqliteStore {
+    pub fn new(custom_dir_path: Option<&str>) -> Result<Self> {
+        let dir = custom_dir_path.unwrap_or("logs");
+        fs::create_dir_all(dir)?;
+        Self::open(Path::new(dir).join("history.db"))
+    }
+
+    /// Open or create the database, tables are created on first use
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let conn = Connection::open(&path)?;
+
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            bail!(
+                "{} was written by a newer version (schema {})",
+                path.display(),
+                version
+            );
+        }
+        if version < 3 {
+            migrate_device_names(&conn)?;
+        }
+        for family in Family::ALL {
+            let device = if family.per_device() {
+                "device TEXT NOT NULL, "
+            } else {
+                ""
+            };
+            let columns = family
+                .columns()
+                .iter()
+                .map(|column| format!("{} REAL", column))
+                .collect::<Vec<_>>()
+                .join(", ");
+            conn.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {table} (timestamp INTEGER NOT NULL, {device}{columns});
+                 CREATE INDEX IF NOT EXISTS {table}_timestamp ON {table} (timestamp);",
+                table = family.table(),
+            ))?;
+        }
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
+        Ok(Self { conn, path })
+    }
+
+    /// Store one logged sample, split over the family tables. Per-device values
+    /// are the comma separated CSV columns, empty ones are stored as NULL
+    pub fn insert(&mut self, entry: &CsvCpuLogEntry, names: &DeviceNames) -> Result<()> {
+        let timestamp = DateTime::parse_from_rfc3339(&entry.timestamp)
+            .map(|time| time.timestamp_millis())
+            .unwrap_or_else(|_| Local::now().timestamp_millis());
+
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO cpu (timestamp, temperature, usage, power_draw) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                timestamp,
+                entry.temperature,
+                entry.cpu_usage,
+                entry.power_draw
+            ],
+        )?;
+        tx.execute(
+            "INSERT INTO memory (timestamp, used, swap_used) VALUES (?1, ?2, ?3)",
+            params![timestamp, entry.memory_used, entry.swap_used],
+        )?;
+        tx.execute(
+            "INSERT INTO network (timestamp, rx_rate, tx_rate) VALUES (?1, ?2, ?3)",
+            params![timestamp, entry.network_rx_rate, entry.network_tx_rate],
+        )?;
+
+        let devices: [(Family, &Vec<String>, Vec<&String>); 9] = [
+            (
+                Family::Package,
+                &names.packages,
+                vec![&entry.package_temperatures, &entry.package_power_draws],
+            ),
+            (Family::Core, &names.cores, vec![&entry.core_temperatures]),
+            (Family::Dimm, &names.dimms, vec![&entry.dimm_temperatures]),
+            (
+                Family::Gpu,
+                &names.gpus,
+                vec![
+                    &entry.gpu_temperatures,
+                    &entry.gpu_loads,
+                    &entry.gpu_power_draws,
+                    &entry.gpu_vram_used,
+                ],
+            ),
+            (
+                Family::Drive,
+                &names.drives,
+                vec![
+                    &entry.drive_temperatures,
+                    &entry.drive_used_space,
+                    &entry.drive_remaining_life,
+                ],
+            ),
+            (Family::Fan, &names.fans, vec![&entry.fan_rpms]),
+            (Family::Voltage, &names.voltages, vec![&entry.rail_voltages]),
+            (
+                Family::Battery,
+                &names.batteries,
+                vec![&entry.battery_charge_levels, &entry.battery_rates],
+            ),
+            (
+                Family::Psu,
+                &names.psus,
+                vec![&entry.psu_output_powers, &entry.psu_efficiencies],
+            ),
+        ];
+        for (family, names, joined) in devices {
+            let values: Vec<Vec<Option<f64>>> =
+                joined.iter().map(|column| split_values(column)).collect();
+            let count = values.iter().map(Vec::len).max().unwrap_or(0);
+            let sql = format!(
+                "INSERT INTO {} (timestamp, device, {}) VALUES (?1, ?2, {})",
+                family.table(),
+                family.columns().join(", "),
+                (3..3 + family.columns().len())
+                    .map(|i| format!("?{}", i))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            for (position, device) in device_ids(names, count).iter().enumerate() {
+                let row: Vec<Option<f64>> = values
+                    .iter()
+                    .map(|column| column.get(position).copied().flatten())
+                    .collect();
+                let mut params: Vec<&dyn ToSql> = vec![&timestamp, device];
+                params.extend(row.iter().map(|value| value as &dyn ToSql));
+                tx.execute(&sql, params.as_slice())?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Raw values between `from` and `to` (inclusive), oldest first
+    pub fn range(
+        &self,
+        series: &Series,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+    ) -> Result<Vec<Point>> {
+        series.check()?;
+        let sql = format!(
+            "SELECT timestamp, {} {} ORDER BY timestamp",
+            series.column,
+            series.filter()
+        );
+        self.points(&sql, series, from, to, None)
+    }
+
+    /// One aggregated value per `bucket`, buckets are aligned to the Unix epoch
+    /// and empty ones are left out
+    pub fn downsample(
+        &self,
+        series: &Series,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        bucket: Duration,
+        aggregate: Aggregate,
+    ) -> Result<Vec<Point>> {
+        series.check()?;
+        let bucket_ms = (bucket.as_millis() as i64).max(1);
+        let sql = format!(
+            "SELECT (timestamp / ?4) * ?4 AS bucket, {}({}) {} GROUP BY bucket ORDER BY bucket",
+            aggregate.sql(),
+            series.column,
+            series.filter()
+        );
+        self.points(&sql, series, from, to, Some(bucket_ms))
+    }
+
+    /// Single aggregate over the whole range, `None` when there are no values
+    pub fn aggregate(
+        &self,
+        series: &Series,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        aggregate: Aggregate,
+    ) -> Result<Option<f64>> {
+        series.check()?;
+        let sql = format!(
+            "SELECT {}({}) {}",
+            aggregate.sql(),
+            series.column,
+            series.filter()
+        );
+        let (from, to) = (from.timestamp_millis(), to.timestamp_millis());
+        let mut params: Vec<&dyn ToSql> = vec![&from, &to];
+        if let Some(device) = &series.device {
+            params.push(device);
+        }
+        let value = self
+            .conn
+            .query_row(&sql, params.as_slice(), |row| row.get::<_, Option<f64>>(0))
+            .optional()?;
+        Ok(value.flatten())
+    }
+
+    fn points(
+        &self,
+        sql: &str,
+        series: &Series,
+        from: DateTime<Local>,
+        to: DateTime<Local>,
+        bucket_ms: Option<i64>,
+    ) -> Result<Vec<Point>> {
+        let (from, to) = (from.timestamp_millis(), to.timestamp_millis());
+        // ?3 is only used with a device, ?4 only when downsampling
+        let no_device = String::new();
+        let mut params: Vec<&dyn ToSql> = vec![&from, &to];
+        if series.device.is_some() || bucket_ms.is_some() {
+            params.push(series.device.as_ref().unwrap_or(&no_device));
+        }
+        if let Some(bucket_ms) = &bucket_ms {
+            params.push(bucket_ms);
+        }
+
+        let mut stmt = self.conn.prepare(sql)?;
+        let points = stmt
+            .query_map(params.as_slice(), |row| {
+                Ok(Point {
+                    timestamp: row.get(0)?,
+                    value: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(points)
+    }
+}
+
+/// Version 3: device positions become "#N" names. Tables are rebuilt because the old
+/// INTEGER column would turn numeric text back into numbers
+fn migrate_device_names(conn: &Connection) -> Result<()> {
+    for family in Family::ALL.into_iter().filter(Family::per_device) {
+        let table = family.table();
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            continue;
+        }
+        let columns = family.columns().join(", ");
+        let column_types = family
+            .columns()
+            .iter()
+            .map(|column| format!("{} REAL", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        conn.execute_batch(&format!(
+            "BEGIN;
+             DROP INDEX IF EXISTS {table}_timestamp;
+             ALTER TABLE {table} RENAME TO {table}_v2;
+             CREATE TABLE {table} (timestamp INTEGER NOT NULL, device TEXT NOT NULL, {column_types});
+             INSERT INTO {table} SELECT timestamp, '#' || device, {columns} FROM {table}_v2;
+             DROP TABLE {table}_v2;
+             COMMIT;"
+        ))?;
+    }
+    Ok(())
+}
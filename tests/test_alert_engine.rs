@@ -0,0 +1,195 @@
+#[path = "../src/utils/alert_engine.rs"]
+#[allow(dead_code)]
+mod alert_engine;
+#[cfg(test)]
+mod tests {
+    use crate::alert_engine::{
+        motherboard_rules, temperature_rule, user_rules, AlertEngine, AlertEventKind, AlertRule,
+        AlertState, Comparison, Metric, TEMPERATURE_RULE_NAME,
+    };
+    use std::time::{Duration, Instant};
+
+    fn power_rule() -> AlertRule {
+        AlertRule {
+            name: "power".to_string(),
+            enabled: true,
+            notify: true,
+            metric: Metric::Power,
+            comparison: Comparison::Above,
+            threshold: 100.0,
+            clear_threshold: Some(80.0),
+            sustain_secs: 3.0,
+            cooldown_secs: 10.0,
+        }
+    }
+
+    // Feed one power value per second, return the events of every tick
+    fn replay(
+        engine: &mut AlertEngine,
+        start: Instant,
+        values: &[f32],
+    ) -> Vec<Vec<AlertEventKind>> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(second, value)| {
+                let now = start + Duration::from_secs(second as u64);
+                engine
+                    .evaluate(|metric| (metric == Metric::Power).then_some(*value), now)
+                    .into_iter()
+                    .map(|event| event.kind)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fires_after_sustain_and_resolves_below_clear() {
+        let mut engine = AlertEngine::new(vec![power_rule()]);
+        let start = Instant::now();
+
+        let events = replay(
+            &mut engine,
+            start,
+            &[90.0, 120.0, 125.0, 130.0, 128.0, 95.0, 85.0, 79.0],
+        );
+        // Pending from t=1, fires at t=4 after 3s above the threshold
+        assert!(events[..4].iter().all(|e| e.is_empty()));
+        assert_eq!(events[4], vec![AlertEventKind::Fired]);
+        // 95 and 85 are below the threshold but not below the clear threshold
+        assert!(events[5].is_empty() && events[6].is_empty());
+        assert_eq!(events[7], vec![AlertEventKind::Resolved]);
+        assert!(matches!(
+            engine.state("power"),
+            Some(AlertState::Resolved { .. })
+        ));
+    }
+
+    #[test]
+    fn test_short_spike_stays_pending() {
+        let mut engine = AlertEngine::new(vec![power_rule()]);
+        let start = Instant::now();
+
+        let events = replay(&mut engine, start, &[120.0, 130.0, 90.0, 120.0]);
+        assert!(events.iter().all(|e| e.is_empty()));
+        assert_eq!(
+            engine.state("power"),
+            Some(AlertState::Pending {
+                since: start + Duration::from_secs(3)
+            })
+        );
+    }
+
+    #[test]
+    fn test_cooldown_blocks_refiring() {
+        let mut rule = power_rule();
+        rule.sustain_secs = 0.0;
+        let mut engine = AlertEngine::new(vec![rule]);
+        let start = Instant::now();
+
+        // Fire, resolve at t=1, above again during the 10s cooldown
+        let mut values = vec![120.0, 50.0];
+        values.extend([120.0; 10]);
+        let events = replay(&mut engine, start, &values);
+        assert_eq!(events[0], vec![AlertEventKind::Fired]);
+        assert_eq!(events[1], vec![AlertEventKind::Resolved]);
+        assert!(events[2..10].iter().all(|e| e.is_empty()));
+        // Cooldown over at t=11
+        assert_eq!(events[11], vec![AlertEventKind::Fired]);
+    }
+
+    #[test]
+    fn test_missing_value_and_disabled_rule() {
+        let mut disabled = temperature_rule(80.0, true);
+        disabled.enabled = false;
+        let mut engine = AlertEngine::new(vec![power_rule(), disabled]);
+        let start = Instant::now();
+
+        // No power sensor, disabled rules never leave Ok
+        for second in 0..5 {
+            let now = start + Duration::from_secs(second);
+            assert!(engine.evaluate(|_| None, now).is_empty());
+            assert!(engine.evaluate(|_| Some(95.0), now).is_empty());
+        }
+        assert_eq!(engine.state("CPU temperature high"), Some(AlertState::Ok));
+        assert_eq!(engine.firing().count(), 0);
+    }
+
+    #[test]
+    fn test_temperature_rule_hysteresis() {
+        let mut engine = AlertEngine::new(vec![temperature_rule(80.0, true)]);
+        let start = Instant::now();
+        let temp = |value: f32| move |metric| (metric == Metric::Temperature).then_some(value);
+
+        let fired = engine.evaluate(temp(80.0), start);
+        assert_eq!(fired[0].kind, AlertEventKind::Fired);
+        assert_eq!(fired[0].value, 80.0);
+        assert_eq!(engine.firing().count(), 1);
+        assert!(engine.evaluate(temp(76.0), start).is_empty());
+        let resolved = engine.evaluate(temp(74.0), start);
+        assert_eq!(resolved[0].kind, AlertEventKind::Resolved);
+    }
+
+    #[test]
+    fn test_invalid_user_rules_dropped() {
+        let rules = vec![
+            power_rule(),
+            AlertRule {
+                cooldown_secs: -1.0,
+                ..power_rule()
+            },
+            AlertRule {
+                sustain_secs: f32::NAN,
+                ..power_rule()
+            },
+            AlertRule {
+                sustain_secs: 1e20,
+                ..power_rule()
+            },
+            AlertRule {
+                name: TEMPERATURE_RULE_NAME.to_string(),
+                ..power_rule()
+            },
+        ];
+
+        assert_eq!(user_rules(&rules), vec![power_rule()]);
+    }
+
+    #[test]
+    fn test_stalled_fan_fires_after_sustain() {
+        let mut engine = AlertEngine::new(motherboard_rules(5.0));
+        let start = Instant::now();
+        let value_of = |rpm: f32| {
+            move |metric| match metric {
+                Metric::FanRpm => Some(rpm),
+                Metric::RailDeviation => Some(1.0),
+                _ => None,
+            }
+        };
+
+        assert!(engine.evaluate(value_of(0.0), start).is_empty());
+        let events = engine.evaluate(value_of(0.0), start + Duration::from_secs(5));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].rule.name, "Fan stopped");
+        assert_eq!(
+            engine.state("Voltage rail out of spec"),
+            Some(AlertState::Ok)
+        );
+
+        let events = engine.evaluate(value_of(900.0), start + Duration::from_secs(6));
+        assert_eq!(events[0].kind, AlertEventKind::Resolved);
+    }
+
+    #[test]
+    fn test_set_rules_keeps_state_by_name() {
+        let mut rule = power_rule();
+        rule.sustain_secs = 0.0;
+        let mut engine = AlertEngine::new(vec![rule.clone()]);
+        engine.evaluate(|_| Some(120.0), Instant::now());
+
+        rule.threshold = 110.0;
+        engine.set_rules(vec![temperature_rule(80.0, true), rule]);
+        assert!(matches!(engine.state("power"), Some(AlertState::Firing)));
+        assert_eq!(engine.state("CPU temperature high"), Some(AlertState::Ok));
+    }
+}
@@ -0,0 +1,48 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::battery_collector::BatteryData;
+    use crate::collectors::SensorKind::{Level, Power, Voltage};
+    use crate::collectors::SensorSample;
+
+    #[test]
+    fn test_parse_discharging_battery() {
+        // Recorded from a ThinkPad T14 through LHM, unplugged
+        let sensors = vec![
+            SensorSample::new("Charge Level", Level, 76.0),
+            SensorSample::new("Degradation Level", Level, 8.4),
+            SensorSample::new("Discharge Rate", Power, 9.8),
+            SensorSample::new("Voltage", Voltage, 15.9),
+        ];
+
+        let battery = BatteryData::from_sensors("5B10W13930", &sensors);
+        assert_eq!(battery.charge_level, Some(76.0));
+        assert_eq!(battery.wear_level, Some(8.4));
+        assert_eq!(battery.rate, Some(-9.8));
+        assert!(battery.on_battery);
+        assert_eq!(battery.summary(), "76% (-9.8 W)");
+    }
+
+    #[test]
+    fn test_parse_charging_battery() {
+        let sensors = vec![
+            SensorSample::new("Charge Level", Level, 40.0),
+            SensorSample::new("Charge Rate", Power, 45.0),
+        ];
+
+        let battery = BatteryData::from_sensors("Battery", &sensors);
+        assert_eq!(battery.rate, Some(45.0));
+        assert!(!battery.on_battery);
+        assert_eq!(battery.wear_level, None);
+    }
+
+    #[test]
+    fn test_wear_from_capacity() {
+        assert_eq!(BatteryData::wear_from_capacity(45.0, 50.0), Some(10.0));
+        // Some new batteries report more than their design capacity
+        assert_eq!(BatteryData::wear_from_capacity(52.0, 50.0), Some(0.0));
+        assert_eq!(BatteryData::wear_from_capacity(45.0, 0.0), None);
+    }
+}
@@ -0,0 +1,228 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::cpu_collector::{
+        core_clocks_from_sensors, core_temps_from_sensors, CpuData,
+    };
+    use crate::collectors::SensorKind::{Clock, Power, Temperature};
+    use crate::collectors::{
+        CoreStats, PackageReading, ReadingsFuture, SensorBackend, SensorError, SensorReadings,
+        SensorSample,
+    };
+    use sysinfo::System;
+
+    // Replays a fixed list of temperatures, one per poll
+    struct FakeBackend {
+        temps: Vec<f32>,
+        tick: usize,
+    }
+
+    impl SensorBackend for FakeBackend {
+        fn poll(&mut self) -> ReadingsFuture {
+            let temp = self.temps[self.tick % self.temps.len()];
+            self.tick += 1;
+            let readings = SensorReadings {
+                temp: Some(temp),
+                total_power_draw: Some(temp / 2.0),
+                core_power_draw: Some(vec![CoreStats {
+                    name: "Core #1".to_string(),
+                    value: 5.0,
+                }]),
+                ..Default::default()
+            };
+            Box::pin(std::future::ready(Ok(readings)))
+        }
+    }
+
+    fn cpu_data() -> CpuData {
+        let mut system = System::new();
+        system.refresh_cpu_all();
+        CpuData::new(&system)
+    }
+
+    #[tokio::test]
+    async fn test_cpu_data_tracks_fake_backend() {
+        let mut cpu_data = cpu_data();
+        let mut backend = FakeBackend {
+            temps: vec![50.0, 72.5, 45.0, 60.0],
+            tick: 0,
+        };
+
+        for _ in 0..4 {
+            let readings = backend.poll().await.unwrap();
+            cpu_data.update(&readings);
+        }
+
+        assert_eq!(cpu_data.temp, 60.0);
+        assert_eq!(cpu_data.temp_low, 45.0);
+        assert_eq!(cpu_data.temp_high, 72.5);
+        assert_eq!(cpu_data.total_power_draw, 30.0);
+        assert_eq!(cpu_data.core_power_draw.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_missing_values_keep_previous_state() {
+        let mut cpu_data = cpu_data();
+        let mut backend = FakeBackend {
+            temps: vec![55.0],
+            tick: 0,
+        };
+        cpu_data.update(&backend.poll().await.unwrap());
+
+        // A backend without temperature or power must not reset them
+        cpu_data.update(&SensorReadings {
+            usage: Some(12.5),
+            frequency: Some(4.2),
+            ..Default::default()
+        });
+
+        assert_eq!(cpu_data.temp, 55.0);
+        assert_eq!(cpu_data.total_power_draw, 27.5);
+        assert_eq!(cpu_data.usage, 12.5);
+        assert_eq!(cpu_data.current_frequency, 4.2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_until_next_good_reading() {
+        let mut cpu_data = cpu_data();
+        let mut backend = FakeBackend {
+            temps: vec![55.0, 58.0],
+            tick: 0,
+        };
+        cpu_data.update(&backend.poll().await.unwrap());
+
+        // Failed poll keeps the last good values
+        cpu_data.mark_stale();
+        assert!(cpu_data.stale);
+        assert_eq!(cpu_data.temp, 55.0);
+
+        cpu_data.update(&backend.poll().await.unwrap());
+        assert!(!cpu_data.stale);
+        assert_eq!(cpu_data.temp, 58.0);
+    }
+
+    #[test]
+    fn test_unavailable_after_backend_dropped() {
+        let mut cpu_data = cpu_data();
+        cpu_data.update(&SensorReadings {
+            temp: Some(60.0),
+            total_power_draw: Some(40.0),
+            ..Default::default()
+        });
+        cpu_data.mark_stale();
+
+        // The disconnected backend was removed, its last values aren't shown anymore
+        cpu_data.mark_unavailable();
+        assert!(!cpu_data.temp_available);
+        assert!(!cpu_data.power_available);
+    }
+
+    #[test]
+    fn test_dual_socket_aggregate() {
+        let package = |temp: f32, power: f32| PackageReading {
+            name: String::new(),
+            temp,
+            power_draw: Some(power),
+            core_power_draw: vec![CoreStats {
+                name: "Core #1".to_string(),
+                value: power / 10.0,
+            }],
+            core_temps: vec![CoreStats {
+                name: "CPU Core #1".to_string(),
+                value: temp - 2.0,
+            }],
+            core_frequencies: Vec::new(),
+        };
+        let readings =
+            SensorReadings::from_packages(vec![package(62.0, 95.0), package(71.5, 110.0)]);
+
+        // Hottest socket and total power
+        assert_eq!(readings.temp, Some(71.5));
+        assert_eq!(readings.total_power_draw, Some(205.0));
+        let cores = readings.core_power_draw.as_ref().unwrap();
+        assert_eq!(cores[0].name, "S0 Core #1");
+        assert_eq!(cores[1].name, "S1 Core #1");
+        let core_temps = readings.core_temps.as_ref().unwrap();
+        assert_eq!(core_temps[1].name, "S1 CPU Core #1");
+        assert_eq!(core_temps[1].value, 69.5);
+
+        let mut cpu_data = cpu_data();
+        cpu_data.update(&readings);
+        assert_eq!(cpu_data.packages.len(), 2);
+        assert_eq!(cpu_data.packages[1].temp, 71.5);
+    }
+
+    #[test]
+    fn test_failed_family_keeps_cpu_readings() {
+        let mut readings = SensorReadings::from_packages(vec![PackageReading {
+            name: "CPU #0".to_string(),
+            temp: 64.0,
+            power_draw: Some(45.0),
+            core_power_draw: Vec::new(),
+            core_temps: Vec::new(),
+            core_frequencies: Vec::new(),
+        }]);
+        let gpu_error = SensorError::SensorNotFound("GPU".to_string());
+        readings.gpus = readings.family(Err(gpu_error.clone()));
+        readings.drives = readings.family(Ok(Vec::new()));
+
+        assert!(readings.gpus.is_none());
+        assert_eq!(readings.drives.as_ref().map(Vec::len), Some(0));
+        assert_eq!(readings.errors, vec![gpu_error]);
+
+        let mut cpu_data = cpu_data();
+        cpu_data.update(&readings);
+        assert_eq!(cpu_data.temp, 64.0);
+        assert_eq!(cpu_data.total_power_draw, 45.0);
+    }
+
+    #[test]
+    fn test_core_temps_from_sensors() {
+        // Intel sensors recorded through LHM
+        let intel = vec![
+            SensorSample::new("CPU Package", Temperature, 66.0),
+            SensorSample::new("CPU Core #1", Temperature, 61.0),
+            SensorSample::new("CPU Core #2", Temperature, 64.0),
+            SensorSample::new("CPU Core #1 Distance to TjMax", Temperature, 39.0),
+            SensorSample::new("Core Max", Temperature, 64.0),
+            SensorSample::new("Core Average", Temperature, 62.5),
+            SensorSample::new("CPU Cores", Power, 40.0),
+        ];
+        let cores = core_temps_from_sensors(&intel);
+        assert_eq!(cores.len(), 2);
+        assert_eq!(cores[1].name, "CPU Core #2");
+
+        // AMD sensors, one per CCD
+        let amd = vec![
+            SensorSample::new("Core (Tctl/Tdie)", Temperature, 72.0),
+            SensorSample::new("CCD1 (Tdie)", Temperature, 70.0),
+            SensorSample::new("CCD2 (Tdie)", Temperature, 65.0),
+        ];
+        let ccds = core_temps_from_sensors(&amd);
+        assert_eq!(ccds.len(), 2);
+        assert_eq!(ccds[0].value, 70.0);
+    }
+
+    #[test]
+    fn test_core_clocks_prefer_effective() {
+        let sensors = vec![
+            SensorSample::new("Bus Speed", Clock, 100.0),
+            SensorSample::new("Core #1", Clock, 5300.0),
+            SensorSample::new("Core #2", Clock, 4200.0),
+            SensorSample::new("Core #1 (Effective)", Clock, 4870.0),
+            SensorSample::new("Core #2 (Effective)", Clock, 850.0),
+        ];
+        let cores = core_clocks_from_sensors(&sensors);
+        assert_eq!(cores.len(), 2);
+        assert_eq!(cores[0].name, "Core #1");
+        assert_eq!(cores[0].value, 4.87);
+        assert_eq!(cores[1].value, 0.85);
+
+        // Older LHM versions only report the requested clock
+        let cores = core_clocks_from_sensors(&sensors[..3]);
+        assert_eq!(cores.len(), 2);
+        assert_eq!(cores[0].value, 5.3);
+    }
+}
@@ -1,9 +1,15 @@
 #[path = "../src/utils/csv_logger.rs"]
+#[allow(dead_code)]
 mod csv_logger;
 #[cfg(test)]
 mod tests {
-    use crate::csv_logger::{CsvCpuLogEntry, CsvLogger};
-    use chrono::Local;
+    use crate::csv_logger::{
+        archive_finished_logs, archive_log, read_cpu_log, CsvCpuLogEntry, CsvLogger,
+        CsvProcessLogEntry, CsvSchema, LogCompression, MetricGroup, ProcessCsvLogger,
+        CSV_SCHEMA_VERSION,
+    };
+    use chrono::{Duration, Local};
+    use std::fs;
     use tempfile::tempdir;
     #[test]
     fn test_csv_logger_write_read() {
@@ -14,9 +20,10 @@ mod tests {
         let entries = vec![CsvCpuLogEntry {
             timestamp: Local::now().to_string(),
             temperature_unit: "Celsius".to_string(),
-            temperature: 65.5,
+            temperature: Some(65.5),
             cpu_usage: 45.2,
-            power_draw: 35.8,
+            power_draw: Some(35.8),
+            ..Default::default()
         }];
 
         logger.write(entries.clone()).unwrap();
@@ -25,9 +32,9 @@ mod tests {
         // Read back and verify
         let read_entries = logger.read().unwrap();
         assert_eq!(read_entries.len(), 1);
-        assert_eq!(read_entries[0].temperature, 65.5);
+        assert_eq!(read_entries[0].temperature, Some(65.5));
         assert_eq!(read_entries[0].cpu_usage, 45.2);
-        assert_eq!(read_entries[0].power_draw, 35.8);
+        assert_eq!(read_entries[0].power_draw, Some(35.8));
         println!("{:?}", read_entries);
     }
 
@@ -44,9 +51,10 @@ mod tests {
         let entry1 = vec![CsvCpuLogEntry {
             timestamp: "2025-11-18 10:00:00".to_string(),
             temperature_unit: "C".to_string(),
-            temperature: 65.0,
+            temperature: Some(65.0),
             cpu_usage: 50.0,
-            power_draw: 30.0,
+            power_draw: Some(30.0),
+            ..Default::default()
         }];
         logger.write(entry1).unwrap();
         logger.flush_buffer().unwrap(); // Force flush to create file
@@ -55,19 +63,18 @@ mod tests {
         let first_file = logger.path.clone();
         println!("First file: {:?}", first_file);
 
-        // Simulate date change to yesterday (so "today" will be different)
-        let yesterday = Local::now() - chrono::Duration::days(1);
-        logger.timestamp = yesterday;
-
         // Write second entry (should create second file with new date)
         let entry2 = vec![CsvCpuLogEntry {
             timestamp: "2025-11-18 11:00:00".to_string(),
             temperature_unit: "C".to_string(),
-            temperature: 70.0,
+            temperature: Some(70.0),
             cpu_usage: 60.0,
-            power_draw: 35.0,
+            power_draw: Some(35.0),
+            ..Default::default()
         }];
-        logger.write(entry2).unwrap();
+        // Written on the next day
+        let tomorrow = Local::now() + chrono::Duration::days(1);
+        logger.write_at(entry2, tomorrow).unwrap();
         logger.flush_buffer().unwrap(); // Force flush to create file
 
         // Get the second file path
@@ -98,20 +105,281 @@ mod tests {
         let temp_path = temp_dir.path().to_str().unwrap();
 
         let mut logger = CsvLogger::new(Some(temp_path)).unwrap();
+        logger.write_buffer_size = 10;
 
         // Write 5 entries
         for i in 0..5 {
             let entry = vec![CsvCpuLogEntry {
                 timestamp: format!("2025-11-18 10:{:02}:00", i),
                 temperature_unit: "C".to_string(),
-                temperature: 65.0 + i as f32,
+                temperature: Some(65.0 + i as f32),
                 cpu_usage: 50.0,
-                power_draw: 30.0,
+                power_draw: Some(30.0),
+                ..Default::default()
             }];
             logger.write(entry).unwrap();
         }
 
-        assert_eq!(logger.graph_data.len(), 5);
+        assert_eq!(logger.graph_data_buffer.len(), 5);
         assert_eq!(logger.write_buffer.len(), 5);
     }
+
+    #[test]
+    fn test_process_log_write_read() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap();
+        let mut logger = ProcessCsvLogger::new(Some(temp_path)).unwrap();
+
+        let entry = |pid: u32, name: &str, power_draw: &str| CsvProcessLogEntry {
+            timestamp: "2025-11-18 10:00:00".to_string(),
+            pid,
+            name: name.to_string(),
+            cpu_usage: 12.5,
+            memory: 512.0,
+            power_draw: power_draw.to_string(),
+        };
+        logger
+            .write(&[entry(42, "cargo", "8.4"), entry(7, "firefox", "")])
+            .unwrap();
+        logger.write(&[entry(42, "cargo", "9.1")]).unwrap();
+
+        // Separate file from the CPU log, header only written once
+        assert!(logger.path.ends_with(format!(
+            "{}_process_logs.csv",
+            Local::now().format("%d-%m-%Y")
+        )));
+        let read_entries = logger.read().unwrap();
+        assert_eq!(read_entries.len(), 3);
+        assert_eq!(read_entries[0].name, "cargo");
+        assert_eq!(read_entries[1].power_draw, "");
+        assert_eq!(read_entries[2].power_draw, "9.1");
+    }
+
+    fn cpu_entry(temperature: f32, network_rx_rate: f32) -> CsvCpuLogEntry {
+        CsvCpuLogEntry {
+            timestamp: "2025-11-18 10:00:00".to_string(),
+            temperature_unit: "Celsius".to_string(),
+            temperature: Some(temperature),
+            cpu_usage: 40.0,
+            power_draw: Some(30.0),
+            gpu_temperatures: "55.0".to_string(),
+            memory_used: 8.5,
+            network_rx_rate,
+            network_tx_rate: 0.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unavailable_values_logged_empty() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap();
+        let mut logger = CsvLogger::new(Some(temp_path)).unwrap();
+
+        let entry = CsvCpuLogEntry {
+            temperature: None,
+            power_draw: None,
+            ..cpu_entry(60.0, 1.0)
+        };
+        logger.write(vec![entry]).unwrap();
+        logger.flush_buffer().unwrap();
+
+        // Empty cells, not zeros
+        let content = fs::read_to_string(&logger.path).unwrap();
+        assert!(content.lines().last().unwrap().contains(";Celsius;;40.0;;"));
+        let entries = logger.read().unwrap();
+        assert_eq!(entries[0].temperature, None);
+        assert_eq!(entries[0].power_draw, None);
+    }
+
+    #[test]
+    fn test_schema_change_starts_suffixed_file() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap();
+        let mut logger = CsvLogger::new(Some(temp_path)).unwrap();
+        logger.write(vec![cpu_entry(60.0, 1.0)]).unwrap();
+        let full_file = logger.path.clone();
+
+        // Only CPU and network columns from here on
+        let schema = CsvSchema::new(&[MetricGroup::Network]);
+        logger.set_schema(schema.clone()).unwrap();
+        logger.write(vec![cpu_entry(70.0, 2.0)]).unwrap();
+
+        assert_ne!(logger.path, full_file);
+        assert!(logger.path.ends_with(format!(
+            "{}_cpu_logs_2.csv",
+            Local::now().format("%d-%m-%Y")
+        )));
+        assert_eq!(
+            CsvSchema::read(&full_file).unwrap(),
+            Some(CsvSchema::default())
+        );
+        assert_eq!(CsvSchema::read(&logger.path).unwrap(), Some(schema.clone()));
+        assert_eq!(
+            schema.columns,
+            [
+                "timestamp",
+                "temperature_unit",
+                "temperature",
+                "cpu_usage",
+                "power_draw",
+                "network_rx_rate",
+                "network_tx_rate"
+            ]
+        );
+
+        // Disabled columns read back as defaults
+        let entries = logger.read().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].temperature, Some(70.0));
+        assert_eq!(entries[0].network_rx_rate, 2.0);
+        assert_eq!(entries[0].gpu_temperatures, "");
+        assert_eq!(entries[0].memory_used, 0.0);
+        assert_eq!(
+            read_cpu_log(&full_file).unwrap()[0].gpu_temperatures,
+            "55.0"
+        );
+
+        // Same schema again appends to the matching file without another header
+        let mut reopened = CsvLogger::with_schema(Some(temp_path), schema).unwrap();
+        reopened.write(vec![cpu_entry(75.0, 3.0)]).unwrap();
+        assert_eq!(reopened.path, logger.path);
+        assert_eq!(reopened.read().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_reads_unversioned_schema() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap();
+        // Five column file from before versioned schemas
+        let old_file = temp_dir
+            .path()
+            .join(format!("{}_cpu_logs.csv", Local::now().format("%d-%m-%Y")));
+        fs::write(
+            &old_file,
+            "timestamp;temperature_unit;temperature;cpu_usage;power_draw\n\
+             2025-11-18 10:00:00;Celsius;62.5;30;25.5\n",
+        )
+        .unwrap();
+
+        let old_schema = CsvSchema::read(&old_file).unwrap().unwrap();
+        assert_eq!(old_schema.version, 1);
+        assert_eq!(old_schema.columns.len(), 5);
+
+        let entries = read_cpu_log(&old_file).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].temperature, Some(62.5));
+        assert_eq!(entries[0].network_rx_rate, 0.0);
+
+        // Appending never mixes schemas, today's old file is left as it was
+        let mut logger = CsvLogger::new(Some(temp_path)).unwrap();
+        logger.write(vec![cpu_entry(65.0, 1.0)]).unwrap();
+        assert_ne!(logger.path, old_file);
+        assert_eq!(read_cpu_log(&old_file).unwrap().len(), 1);
+        let new_schema = CsvSchema::read(&logger.path).unwrap().unwrap();
+        assert_eq!(new_schema.version, CSV_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_finished_days_compressed() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap();
+        let mut logger = CsvLogger::new(Some(temp_path)).unwrap();
+
+        // Logs left by an earlier run, plus a roll-up that stays plain
+        let yesterday = Local::now() - Duration::days(1);
+        let file = |name: &str| {
+            temp_dir
+                .path()
+                .join(format!("{}_cpu_logs{}", yesterday.format("%d-%m-%Y"), name))
+        };
+        let yesterday_file = file(".csv");
+        logger.timestamp = yesterday;
+        logger.update_path(yesterday_file.clone());
+        logger.write_buffer.push(cpu_entry(60.0, 1.0));
+        logger.flush_buffer().unwrap();
+        fs::copy(&yesterday_file, file("_2.csv")).unwrap();
+        fs::write(file("_1m.csv"), "timestamp\n").unwrap();
+
+        logger
+            .write_at(vec![cpu_entry(65.0, 2.0)], Local::now())
+            .unwrap();
+        let today = Local::now().date_naive();
+        let compressed =
+            archive_finished_logs(temp_dir.path(), LogCompression::Gzip, today).unwrap();
+
+        let archive = file(".csv.gz");
+        assert_eq!(compressed, 2);
+        assert!(!yesterday_file.exists());
+        assert!(archive.exists());
+        assert!(file("_2.csv.gz").exists());
+        assert!(file("_1m.csv").exists());
+        // Both the archive and the old plain path read transparently
+        assert_eq!(read_cpu_log(&archive).unwrap()[0].temperature, Some(60.0));
+        assert_eq!(read_cpu_log(&yesterday_file).unwrap().len(), 1);
+        assert_eq!(
+            CsvSchema::read(&archive).unwrap().unwrap().version,
+            CSV_SCHEMA_VERSION
+        );
+        // Today's log stays plain
+        assert_eq!(logger.read().unwrap()[0].temperature, Some(65.0));
+    }
+
+    #[test]
+    fn test_paused_day_archived_once() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap();
+        let mut logger = CsvLogger::new(Some(temp_path)).unwrap();
+
+        let yesterday = Local::now() - Duration::days(1);
+        let yesterday_file = temp_dir
+            .path()
+            .join(format!("{}_cpu_logs.csv", yesterday.format("%d-%m-%Y")));
+        logger.timestamp = yesterday;
+        logger.update_path(yesterday_file.clone());
+        logger.write_buffer.push(cpu_entry(60.0, 1.0));
+        logger.flush_buffer().unwrap();
+
+        // Logging is paused over midnight and the finished day gets archived
+        let today = Local::now().date_naive();
+        archive_finished_logs(temp_dir.path(), LogCompression::Gzip, today).unwrap();
+        let archive = LogCompression::Gzip.archive_path(&yesterday_file);
+
+        // Quitting or resuming must not recreate yesterday's file
+        logger.flush_buffer().unwrap();
+        logger
+            .write_at(vec![cpu_entry(65.0, 2.0)], Local::now())
+            .unwrap();
+        assert!(!yesterday_file.exists());
+        assert_eq!(
+            archive_finished_logs(temp_dir.path(), LogCompression::Gzip, today).unwrap(),
+            0
+        );
+        assert_eq!(read_cpu_log(&archive).unwrap()[0].temperature, Some(60.0));
+
+        // A plain file next to an archive is left alone instead of replacing it
+        fs::write(&yesterday_file, "timestamp\n").unwrap();
+        assert!(archive_finished_logs(temp_dir.path(), LogCompression::Gzip, today).is_err());
+        assert!(yesterday_file.exists());
+        assert_eq!(read_cpu_log(&archive).unwrap()[0].temperature, Some(60.0));
+    }
+
+    #[test]
+    fn test_zstd_archive_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path().to_str().unwrap();
+        let mut logger = CsvLogger::new(Some(temp_path)).unwrap();
+        logger
+            .write(vec![cpu_entry(61.0, 1.0), cpu_entry(62.0, 1.5)])
+            .unwrap();
+        logger.flush_buffer().unwrap();
+
+        let archive = archive_log(&logger.path, LogCompression::Zstd).unwrap();
+
+        assert_eq!(archive.extension().unwrap(), "zst");
+        assert!(!logger.path.exists());
+        let entries = read_cpu_log(&archive).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].network_rx_rate, 1.5);
+    }
 }
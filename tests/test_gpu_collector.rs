@@ -0,0 +1,90 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::gpu_collector::GpuData;
+    use crate::collectors::SensorKind::{Clock, Load, Power, SmallData, Temperature};
+    use crate::collectors::{SensorKind, SensorSample};
+
+    fn samples(list: &[(&str, SensorKind, f32)]) -> Vec<SensorSample> {
+        list.iter()
+            .map(|(name, kind, value)| SensorSample::new(*name, *kind, *value))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_nvidia_sensors() {
+        // Recorded from an RTX 3080 through LHM
+        let sensors = samples(&[
+            ("GPU Core", Temperature, 64.0),
+            ("GPU Hot Spot", Temperature, 76.5),
+            ("GPU Memory Junction", Temperature, 82.0),
+            ("GPU Core", Load, 97.0),
+            ("GPU Memory Controller", Load, 55.0),
+            ("GPU Video Engine", Load, 0.0),
+            ("GPU Package", Power, 318.2),
+            ("GPU Core", Clock, 1905.0),
+            ("GPU Memory", Clock, 9501.0),
+            ("GPU Memory Free", SmallData, 2048.0),
+            ("GPU Memory Used", SmallData, 8192.0),
+            ("GPU Memory Total", SmallData, 10240.0),
+        ]);
+
+        let gpu = GpuData::from_sensors("NVIDIA GeForce RTX 3080", &sensors);
+        assert_eq!(gpu.name, "NVIDIA GeForce RTX 3080");
+        assert_eq!(gpu.core_temp, Some(64.0));
+        assert_eq!(gpu.hotspot_temp, Some(76.5));
+        assert_eq!(gpu.memory_temp, Some(82.0));
+        assert_eq!(gpu.load, Some(97.0));
+        assert_eq!(gpu.power_draw, Some(318.2));
+        assert_eq!(gpu.core_clock, Some(1905.0));
+        assert_eq!(gpu.memory_clock, Some(9501.0));
+        assert_eq!(gpu.vram_used, Some(8192.0));
+        assert_eq!(gpu.vram_total, Some(10240.0));
+    }
+
+    #[test]
+    fn test_parse_amd_sensors() {
+        // Recorded from an RX 6800 XT, no "GPU Memory Total" sensor
+        let sensors = samples(&[
+            ("GPU Core", Temperature, 58.0),
+            ("GPU Hot Spot", Temperature, 71.0),
+            ("GPU Memory", Temperature, 66.0),
+            ("GPU Core", Load, 88.0),
+            ("GPU Core", Power, 190.0),
+            ("GPU PPT", Power, 255.0),
+            ("GPU Core", Clock, 2250.0),
+            ("GPU Memory", Clock, 1990.0),
+            ("GPU Memory Used", SmallData, 6000.0),
+            ("GPU Memory Free", SmallData, 10384.0),
+        ]);
+
+        let gpu = GpuData::from_sensors("AMD Radeon RX 6800 XT", &sensors);
+        assert_eq!(gpu.core_temp, Some(58.0));
+        assert_eq!(gpu.memory_temp, Some(66.0));
+        // Board power is preferred over core power
+        assert_eq!(gpu.power_draw, Some(255.0));
+        // Total from used + free
+        assert_eq!(gpu.vram_total, Some(16384.0));
+    }
+
+    #[test]
+    fn test_parse_integrated_gpu_sensors() {
+        // Intel iGPU only reports D3D and power sensors
+        let sensors = samples(&[
+            ("D3D 3D", Load, 12.0),
+            ("D3D Video Decode", Load, 3.0),
+            ("GPU Power", Power, 4.5),
+            ("D3D Dedicated Memory Used", SmallData, 128.0),
+        ]);
+
+        let gpu = GpuData::from_sensors("Intel(R) UHD Graphics 770", &sensors);
+        assert_eq!(gpu.core_temp, None);
+        assert_eq!(gpu.hotspot_temp, None);
+        assert_eq!(gpu.load, Some(12.0));
+        assert_eq!(gpu.power_draw, Some(4.5));
+        assert_eq!(gpu.vram_used, Some(128.0));
+        assert_eq!(gpu.vram_total, None);
+    }
+}
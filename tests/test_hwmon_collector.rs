@@ -0,0 +1,339 @@
+#![cfg(target_os = "linux")]
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::hwmon_collector::HwmonBackend;
+    use crate::collectors::topology_collector::CoreType;
+    use std::fs;
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+    use tempfile::tempdir;
+
+    fn write(root: &Path, file: &str, contents: &str) {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    // Fake sysfs tree of a single package Intel CPU
+    fn fake_sysfs(root: &Path) {
+        // NVMe sensor that must be ignored
+        write(root, "class/hwmon/hwmon0/name", "nvme\n");
+        write(root, "class/hwmon/hwmon0/temp1_input", "38850\n");
+
+        write(root, "class/hwmon/hwmon1/name", "coretemp\n");
+        write(root, "class/hwmon/hwmon1/temp1_label", "Package id 0\n");
+        write(root, "class/hwmon/hwmon1/temp1_input", "61000\n");
+        write(root, "class/hwmon/hwmon1/temp2_label", "Core 0\n");
+        write(root, "class/hwmon/hwmon1/temp2_input", "58000\n");
+
+        write(root, "class/powercap/intel-rapl:0/name", "package-0\n");
+        write(root, "class/powercap/intel-rapl:0/energy_uj", "1000000\n");
+        write(
+            root,
+            "class/powercap/intel-rapl:0/max_energy_range_uj",
+            "262143328850\n",
+        );
+        write(root, "class/powercap/intel-rapl:0:0/name", "core\n");
+        write(root, "class/powercap/intel-rapl:0:0/energy_uj", "500000\n");
+
+        write(
+            root,
+            "devices/system/cpu/cpu0/cpufreq/scaling_cur_freq",
+            "3000000\n",
+        );
+        write(
+            root,
+            "devices/system/cpu/cpu1/cpufreq/scaling_cur_freq",
+            "4000000\n",
+        );
+        write(root, "devices/system/cpu/cpufreq/boost", "1\n");
+    }
+
+    #[test]
+    fn test_reads_temperature_and_frequency() {
+        let root = tempdir().unwrap();
+        fake_sysfs(root.path());
+        let mut backend = HwmonBackend::new(root.path());
+
+        let readings = backend.read(Instant::now());
+        assert_eq!(readings.temp, Some(61.0));
+        assert_eq!(readings.frequency, Some(3.5));
+        let core_temps = readings.core_temps.unwrap();
+        assert_eq!(core_temps.len(), 1);
+        assert_eq!(core_temps[0].name, "Core 0");
+        // Power needs two samples
+        assert_eq!(readings.total_power_draw, None);
+    }
+
+    #[test]
+    fn test_rapl_power_from_energy_delta() {
+        let root = tempdir().unwrap();
+        fake_sysfs(root.path());
+        let mut backend = HwmonBackend::new(root.path());
+
+        let start = Instant::now();
+        backend.read(start);
+        write(
+            root.path(),
+            "class/powercap/intel-rapl:0/energy_uj",
+            "91000000\n",
+        );
+        write(
+            root.path(),
+            "class/powercap/intel-rapl:0:0/energy_uj",
+            "60500000\n",
+        );
+        let readings = backend.read(start + Duration::from_secs(2));
+
+        assert_eq!(readings.total_power_draw, Some(45.0));
+        let cores = readings.core_power_draw.unwrap();
+        assert_eq!(cores.len(), 1);
+        assert_eq!(cores[0].name, "core");
+        assert_eq!(cores[0].value, 30.0);
+    }
+
+    #[test]
+    fn test_rapl_skips_platform_and_uncore_zones() {
+        let root = tempdir().unwrap();
+        fake_sysfs(root.path());
+        // Laptop platform zone and an uncore subzone, both must not add to the CPU power
+        write(
+            root.path(),
+            "class/powercap/intel-rapl:0:1/name",
+            "uncore\n",
+        );
+        write(
+            root.path(),
+            "class/powercap/intel-rapl:0:1/energy_uj",
+            "0\n",
+        );
+        write(root.path(), "class/powercap/intel-rapl:1/name", "psys\n");
+        write(root.path(), "class/powercap/intel-rapl:1/energy_uj", "0\n");
+        let mut backend = HwmonBackend::new(root.path());
+
+        let start = Instant::now();
+        backend.read(start);
+        for (zone, uj) in [
+            ("intel-rapl:0", "21000000"),
+            ("intel-rapl:0:0", "10500000"),
+            ("intel-rapl:0:1", "4000000"),
+            ("intel-rapl:1", "50000000"),
+        ] {
+            write(
+                root.path(),
+                &format!("class/powercap/{zone}/energy_uj"),
+                &format!("{uj}\n"),
+            );
+        }
+        let readings = backend.read(start + Duration::from_secs(1));
+
+        assert_eq!(readings.total_power_draw, Some(20.0));
+        let cores = readings.core_power_draw.unwrap();
+        assert_eq!(cores.len(), 1);
+        assert_eq!(cores[0].name, "core");
+        assert_eq!(cores[0].value, 10.0);
+    }
+
+    #[test]
+    fn test_rapl_counter_wraparound() {
+        let root = tempdir().unwrap();
+        write(
+            root.path(),
+            "class/powercap/intel-rapl:0/name",
+            "package-0\n",
+        );
+        write(
+            root.path(),
+            "class/powercap/intel-rapl:0/energy_uj",
+            "900\n",
+        );
+        write(
+            root.path(),
+            "class/powercap/intel-rapl:0/max_energy_range_uj",
+            "1000\n",
+        );
+        let mut backend = HwmonBackend::new(root.path());
+
+        let start = Instant::now();
+        backend.read(start);
+        write(
+            root.path(),
+            "class/powercap/intel-rapl:0/energy_uj",
+            "100\n",
+        );
+        let readings = backend.read(start + Duration::from_secs(1));
+
+        // 100uJ to the wrap point + 100uJ after it
+        assert_eq!(readings.total_power_draw, Some(0.0002));
+    }
+
+    #[test]
+    fn test_dual_socket_packages() {
+        let root = tempdir().unwrap();
+        let path = root.path();
+        // Second socket's hwmon device comes first, its id is in the label
+        write(path, "class/hwmon/hwmon1/name", "coretemp\n");
+        write(path, "class/hwmon/hwmon1/temp1_label", "Package id 1\n");
+        write(path, "class/hwmon/hwmon1/temp1_input", "72000\n");
+        write(path, "class/hwmon/hwmon1/temp2_label", "Core 0\n");
+        write(path, "class/hwmon/hwmon1/temp2_input", "70000\n");
+        write(path, "class/hwmon/hwmon2/name", "coretemp\n");
+        write(path, "class/hwmon/hwmon2/temp1_label", "Package id 0\n");
+        write(path, "class/hwmon/hwmon2/temp1_input", "55000\n");
+        write(path, "class/hwmon/hwmon2/temp2_label", "Core 0\n");
+        write(path, "class/hwmon/hwmon2/temp2_input", "53000\n");
+        for socket in 0..2 {
+            let zone = format!("class/powercap/intel-rapl:{}", socket);
+            write(
+                path,
+                &format!("{}/name", zone),
+                &format!("package-{}\n", socket),
+            );
+            write(path, &format!("{}/energy_uj", zone), "0\n");
+            let cpu = format!("devices/system/cpu/cpu{}", socket);
+            write(
+                path,
+                &format!("{}/cpufreq/scaling_cur_freq", cpu),
+                "3000000\n",
+            );
+            write(
+                path,
+                &format!("{}/topology/physical_package_id", cpu),
+                &format!("{}\n", socket),
+            );
+        }
+        let mut backend = HwmonBackend::new(path);
+
+        let start = Instant::now();
+        backend.read(start);
+        write(path, "class/powercap/intel-rapl:0/energy_uj", "40000000\n");
+        write(path, "class/powercap/intel-rapl:1/energy_uj", "90000000\n");
+        let readings = backend.read(start + Duration::from_secs(1));
+
+        // Hottest socket and the power of both
+        assert_eq!(readings.temp, Some(72.0));
+        assert_eq!(readings.total_power_draw, Some(130.0));
+        let packages = readings.packages.unwrap();
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "CPU #0");
+        assert_eq!(packages[0].temp, 55.0);
+        assert_eq!(packages[0].power_draw, Some(40.0));
+        assert_eq!(packages[1].power_draw, Some(90.0));
+        assert_eq!(packages[1].core_frequencies[0].name, "cpu1");
+        let core_temps = readings.core_temps.unwrap();
+        assert_eq!(core_temps[0].name, "S0 Core 0");
+        assert_eq!(core_temps[1].name, "S1 Core 0");
+    }
+
+    #[test]
+    fn test_reads_discharging_battery() {
+        let root = tempdir().unwrap();
+        let supply = "class/power_supply";
+        write(root.path(), &format!("{supply}/AC/type"), "Mains\n");
+        write(root.path(), &format!("{supply}/AC/online"), "0\n");
+        write(root.path(), &format!("{supply}/BAT0/type"), "Battery\n");
+        write(
+            root.path(),
+            &format!("{supply}/BAT0/status"),
+            "Discharging\n",
+        );
+        write(root.path(), &format!("{supply}/BAT0/capacity"), "64\n");
+        write(
+            root.path(),
+            &format!("{supply}/BAT0/power_now"),
+            "11500000\n",
+        );
+        write(
+            root.path(),
+            &format!("{supply}/BAT0/energy_full"),
+            "45600000\n",
+        );
+        write(
+            root.path(),
+            &format!("{supply}/BAT0/energy_full_design"),
+            "57000000\n",
+        );
+        let mut backend = HwmonBackend::new(root.path());
+
+        let batteries = backend.read(Instant::now()).batteries.unwrap();
+        assert_eq!(batteries.len(), 1);
+        assert_eq!(batteries[0].name, "BAT0");
+        assert_eq!(batteries[0].charge_level, Some(64.0));
+        assert_eq!(batteries[0].rate, Some(-11.5));
+        assert_eq!(batteries[0].wear_level, Some(20.0));
+        assert!(batteries[0].on_battery);
+    }
+
+    #[test]
+    fn test_reads_hybrid_topology() {
+        // Two hyperthreaded P-cores (cpu0-3) and a module of two E-cores (cpu4-5) sharing
+        // an L2, all sharing an L3
+        let root = tempdir().unwrap();
+        write(root.path(), "devices/cpu_atom/cpus", "4-5\n");
+        for (cpu, core_id) in [(0, 0), (1, 0), (2, 4), (3, 4), (4, 8), (5, 9)] {
+            let dir = format!("devices/system/cpu/cpu{}", cpu);
+            write(
+                root.path(),
+                &format!("{dir}/topology/core_id"),
+                &format!("{core_id}\n"),
+            );
+            write(
+                root.path(),
+                &format!("{dir}/topology/physical_package_id"),
+                "0\n",
+            );
+            write(root.path(), &format!("{dir}/cache/index2/level"), "2\n");
+            write(
+                root.path(),
+                &format!("{dir}/cache/index2/id"),
+                &format!("{}\n", core_id.min(8)),
+            );
+            write(root.path(), &format!("{dir}/cache/index3/level"), "3\n");
+            write(root.path(), &format!("{dir}/cache/index3/id"), "0\n");
+        }
+        let backend = HwmonBackend::new(root.path());
+
+        let topology = backend.read_topology();
+        assert!(topology.is_hybrid());
+        assert_eq!(topology.cores.len(), 6);
+        assert!(topology.get(1).unwrap().smt_sibling);
+        assert_eq!(topology.get(3).unwrap().physical_core, 1);
+        assert_eq!(topology.get(4).unwrap().core_type, CoreType::Efficiency);
+        assert_eq!(topology.get(5).unwrap().physical_core, 3);
+        // P-cores grouped by the L3, the E-core module by its L2
+        let cluster = |index| topology.get(index).unwrap().cluster;
+        assert_eq!(cluster(0), cluster(2));
+        assert_eq!(cluster(4), cluster(5));
+        assert_ne!(cluster(0), cluster(4));
+    }
+
+    #[test]
+    fn test_unreadable_rapl_counter_reported() {
+        let root = tempdir().unwrap();
+        fake_sysfs(root.path());
+        // Stands in for an energy_uj only root can read, which can't be faked as root
+        fs::remove_file(root.path().join("class/powercap/intel-rapl:0/energy_uj")).unwrap();
+        fs::create_dir(root.path().join("class/powercap/intel-rapl:0/energy_uj")).unwrap();
+        let mut backend = HwmonBackend::new(root.path());
+
+        let readings = backend.read(Instant::now());
+        assert_eq!(readings.errors.len(), 1);
+        assert!(readings.errors[0].to_string().contains("RAPL energy_uj"));
+        // Everything else is still read
+        assert_eq!(readings.temp, Some(61.0));
+    }
+
+    #[test]
+    fn test_missing_sysfs_reports_nothing() {
+        let root = tempdir().unwrap();
+        let mut backend = HwmonBackend::new(root.path().join("does-not-exist"));
+
+        let readings = backend.read(Instant::now());
+        assert!(readings.temp.is_none());
+        assert!(readings.frequency.is_none());
+        assert!(readings.total_power_draw.is_none());
+    }
+}
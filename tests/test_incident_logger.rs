@@ -0,0 +1,63 @@
+#[path = "../src/utils/incident_logger.rs"]
+mod incident_logger;
+#[cfg(test)]
+mod tests {
+    use crate::incident_logger::{Incident, IncidentLogger};
+    use tempfile::tempdir;
+
+    fn incident(temperature: f32) -> Incident {
+        Incident {
+            timestamp: "2025-11-18T10:00:00+02:00".to_string(),
+            temperature,
+            threshold: 80.0,
+            frequency: 4.7,
+            core_usage: "98.0,12.5".to_string(),
+            top_processes: "cargo (4242) 45.0%,rustc (4300) 20.5%".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_crossing_rearms_below_threshold() {
+        let temp_dir = tempdir().unwrap();
+        let mut logger = IncidentLogger::new(temp_dir.path().to_str());
+
+        assert!(!logger.crossed(70.0, 80.0));
+        assert!(logger.crossed(80.0, 80.0));
+        // Still above, same incident
+        assert!(!logger.crossed(85.0, 80.0));
+        // Hovering around the threshold doesn't re-arm
+        assert!(!logger.crossed(79.0, 80.0));
+        assert!(!logger.crossed(81.0, 80.0));
+        assert!(!logger.crossed(74.0, 80.0));
+        assert!(logger.crossed(81.0, 80.0));
+    }
+
+    #[test]
+    fn test_incidents_survive_restart() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().to_str();
+        let mut logger = IncidentLogger::new(dir);
+        logger.record(incident(82.0)).unwrap();
+        logger.record(incident(91.5)).unwrap();
+        assert!(logger.path.ends_with("incidents.csv"));
+
+        let reopened = IncidentLogger::new(dir);
+        assert_eq!(reopened.incidents.len(), 2);
+        assert_eq!(reopened.incidents[1], incident(91.5));
+    }
+
+    #[test]
+    fn test_unreadable_rows_skipped() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().to_str();
+        let mut logger = IncidentLogger::new(dir);
+        logger.record(incident(82.0)).unwrap();
+        let mut content = std::fs::read_to_string(&logger.path).unwrap();
+        content.push_str("garbage;not a number\n");
+        std::fs::write(&logger.path, content).unwrap();
+        logger.record(incident(91.5)).unwrap();
+
+        let reopened = IncidentLogger::new(dir);
+        assert_eq!(reopened.incidents, vec![incident(82.0), incident(91.5)]);
+    }
+}
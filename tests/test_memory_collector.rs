@@ -0,0 +1,68 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::memory_collector::{top_consumers, DimmReading, MemoryData};
+    use crate::collectors::SensorKind::{Data, Load, Temperature};
+    use crate::collectors::{SensorReadings, SensorSample};
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_top_consumers_grouped_by_name() {
+        let processes = vec![
+            ("firefox".to_string(), 400 * MB),
+            ("code".to_string(), 700 * MB),
+            ("firefox".to_string(), 500 * MB),
+            ("bash".to_string(), 5 * MB),
+            ("firefox".to_string(), 100 * MB),
+        ];
+
+        let top = top_consumers(processes, 2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].name, "firefox");
+        assert_eq!(top[0].memory, 1000.0);
+        assert_eq!(top[1].name, "code");
+    }
+
+    #[test]
+    fn test_dimm_without_sensor_is_skipped() {
+        // DDR5 module with an SPD hub sensor
+        let ddr5 = vec![SensorSample::new("Temperature", Temperature, 41.5)];
+        // "Generic Memory" only reports usage
+        let generic = vec![
+            SensorSample::new("Memory", Load, 48.0),
+            SensorSample::new("Memory Used", Data, 15.4),
+        ];
+
+        let dimm = DimmReading::from_sensors("DIMM #1", &ddr5).unwrap();
+        assert_eq!(dimm.temp, 41.5);
+        assert!(DimmReading::from_sensors("Generic Memory", &generic).is_none());
+    }
+
+    #[test]
+    fn test_usage_update_keeps_dimm_temps() {
+        let mut memory = MemoryData::default();
+        memory.update(&SensorReadings {
+            dimm_temps: Some(vec![DimmReading {
+                name: "DIMM #1".to_string(),
+                temp: 40.0,
+            }]),
+            ..Default::default()
+        });
+        // sysinfo readings don't include DIMM temperatures
+        memory.update(&SensorReadings {
+            memory: Some(MemoryData {
+                total: 32.0,
+                used: 8.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        assert_eq!(memory.used, 8.0);
+        assert_eq!(memory.usage_percent(), 25.0);
+        assert_eq!(memory.dimm_temps.len(), 1);
+    }
+}
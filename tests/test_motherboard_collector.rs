@@ -0,0 +1,54 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::motherboard_collector::MotherboardData;
+    use crate::collectors::SensorKind::{Control, Fan, Voltage};
+    use crate::collectors::SensorSample;
+
+    #[test]
+    fn test_parse_super_io_sensors() {
+        // Recorded from a Nuvoton NCT6798D through LHM
+        let sensors = vec![
+            SensorSample::new("Vcore", Voltage, 1.264),
+            SensorSample::new("+12V", Voltage, 12.096),
+            SensorSample::new("+5V", Voltage, 5.04),
+            SensorSample::new("+3.3V", Voltage, 3.328),
+            SensorSample::new("Fan #1", Fan, 1210.0),
+            SensorSample::new("Fan #2", Fan, 845.0),
+            SensorSample::new("Fan #1", Control, 48.0),
+        ];
+
+        let motherboard = MotherboardData::from_sensors(&sensors);
+        assert_eq!(motherboard.fans.len(), 2);
+        assert_eq!(motherboard.fans[0].control, Some(48.0));
+        assert_eq!(motherboard.fans[1].control, None);
+        assert_eq!(motherboard.voltages.len(), 4);
+        assert_eq!(motherboard.voltages[0].nominal, None);
+        assert_eq!(motherboard.voltages[1].nominal, Some(12.0));
+        assert!(motherboard.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_warnings_for_stalled_fan_and_sagging_rail() {
+        let sensors = vec![
+            SensorSample::new("+12V", Voltage, 11.2),
+            SensorSample::new("+5V", Voltage, 5.1),
+            SensorSample::new("CPU Fan", Fan, 0.0),
+            SensorSample::new("CPU Fan", Control, 60.0),
+            // Fan header turned off on purpose
+            SensorSample::new("Fan #3", Fan, 0.0),
+            SensorSample::new("Fan #3", Control, 0.0),
+        ];
+
+        let motherboard = MotherboardData::from_sensors(&sensors);
+        assert_eq!(
+            motherboard.warnings(),
+            vec!["CPU Fan stopped", "+12V at 11.20 V"]
+        );
+        // Values the fan and rail alerts watch
+        assert_eq!(motherboard.slowest_fan_rpm(), Some(0.0));
+        assert!((motherboard.worst_rail_deviation().unwrap() - 6.67).abs() < 0.01);
+    }
+}
@@ -0,0 +1,86 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::network_collector::{NetworkCounters, NetworkTracker};
+    use std::time::{Duration, Instant};
+
+    fn counters(rx_bytes: u64, tx_bytes: u64, rx_errors: u64) -> NetworkCounters {
+        NetworkCounters {
+            rx_bytes,
+            tx_bytes,
+            rx_errors,
+            tx_errors: 0,
+        }
+    }
+
+    #[test]
+    fn test_rates_and_totals_since_start() {
+        let mut tracker = NetworkTracker::default();
+        let start = Instant::now();
+
+        let first = tracker.read(vec![("eth0".to_string(), counters(5000, 1000, 2))], start);
+        assert_eq!(first[0].rx_rate, 0.0);
+        assert_eq!(first[0].rx_total, 0);
+
+        tracker.read(
+            vec![("eth0".to_string(), counters(7000, 1500, 2))],
+            start + Duration::from_secs(1),
+        );
+        let third = tracker.read(
+            vec![("eth0".to_string(), counters(11000, 2500, 5))],
+            start + Duration::from_secs(3),
+        );
+
+        assert_eq!(third[0].rx_rate, 2000.0);
+        assert_eq!(third[0].tx_rate, 500.0);
+        assert_eq!(third[0].rx_total, 6000);
+        assert_eq!(third[0].tx_total, 1500);
+        assert_eq!(third[0].rx_errors, 3);
+    }
+
+    #[test]
+    fn test_loopback_and_idle_interfaces_skipped() {
+        let mut tracker = NetworkTracker::default();
+        let interfaces = tracker.read(
+            vec![
+                ("wlan0".to_string(), counters(100, 100, 0)),
+                ("lo".to_string(), counters(9000, 9000, 0)),
+                ("docker0".to_string(), counters(0, 0, 0)),
+            ],
+            Instant::now(),
+        );
+
+        assert_eq!(interfaces.len(), 1);
+        assert_eq!(interfaces[0].name, "wlan0");
+    }
+
+    #[test]
+    fn test_counter_reset_is_not_a_spike() {
+        let mut tracker = NetworkTracker::default();
+        let start = Instant::now();
+        tracker.read(vec![("eth0".to_string(), counters(50000, 50000, 0))], start);
+        tracker.read(
+            vec![("eth0".to_string(), counters(60000, 50000, 0))],
+            start + Duration::from_secs(1),
+        );
+
+        // Interface re-created, counters start over
+        let interfaces = tracker.read(
+            vec![("eth0".to_string(), counters(100, 100, 0))],
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(interfaces[0].rx_rate, 0.0);
+        assert_eq!(interfaces[0].rx_total, 10000);
+
+        // Totals keep counting from the new counters
+        let interfaces = tracker.read(
+            vec![("eth0".to_string(), counters(2100, 600, 0))],
+            start + Duration::from_secs(3),
+        );
+        assert_eq!(interfaces[0].rx_rate, 2000.0);
+        assert_eq!(interfaces[0].rx_total, 12000);
+        assert_eq!(interfaces[0].tx_total, 500);
+    }
+}
@@ -0,0 +1,90 @@
+#[path = "../src/utils/alert_engine.rs"]
+#[allow(dead_code)]
+mod alert_engine;
+#[path = "../src/utils/notifications.rs"]
+#[allow(dead_code)]
+mod notifications;
+#[cfg(test)]
+mod tests {
+    use crate::alert_engine::{temperature_rule, AlertEngine, AlertEvent, Metric};
+    use crate::notifications::{notify_alerts, Notification, NotificationSink, Urgency};
+    use std::time::{Duration, Instant};
+
+    // Records notifications instead of showing them, fails when told to
+    #[derive(Default)]
+    struct MockSink {
+        sent: Vec<Notification>,
+        fail: bool,
+    }
+
+    impl NotificationSink for MockSink {
+        fn notify(&mut self, notification: &Notification) -> Result<(), String> {
+            if self.fail {
+                return Err("no notification service".to_string());
+            }
+            self.sent.push(notification.clone());
+            Ok(())
+        }
+    }
+
+    // Temperature events for a run of readings, one second apart
+    fn temperature_events(notify: bool, temps: &[f32]) -> Vec<AlertEvent> {
+        let mut engine = AlertEngine::new(vec![temperature_rule(80.0, notify)]);
+        let start = Instant::now();
+        temps
+            .iter()
+            .enumerate()
+            .flat_map(|(second, temp)| {
+                let now = start + Duration::from_secs(second as u64);
+                engine.evaluate(
+                    |metric| (metric == Metric::Temperature).then_some(*temp),
+                    now,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_notifies_when_alert_fires() {
+        let events = temperature_events(true, &[70.0, 85.0, 86.0, 70.0]);
+        assert_eq!(events.len(), 2); // Fired and resolved
+
+        let mut sink = MockSink::default();
+        let errors = notify_alerts(&mut sink, &events);
+
+        assert!(errors.is_empty());
+        // Only the fired alert is worth a notification
+        assert_eq!(
+            sink.sent,
+            vec![Notification {
+                summary: "CPU temperature high".to_string(),
+                body: "CPU temperature is 85.0°C (threshold 80.0°C)".to_string(),
+                urgency: Urgency::Critical,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_disabled_notifications_are_skipped() {
+        let events = temperature_events(false, &[70.0, 85.0]);
+        assert_eq!(events.len(), 1);
+
+        let mut sink = MockSink::default();
+        notify_alerts(&mut sink, &events);
+
+        assert!(sink.sent.is_empty());
+    }
+
+    #[test]
+    fn test_delivery_errors_are_returned() {
+        let events = temperature_events(true, &[85.0]);
+        let mut sink = MockSink {
+            fail: true,
+            ..MockSink::default()
+        };
+
+        let errors = notify_alerts(&mut sink, &events);
+
+        assert_eq!(errors, vec!["no notification service".to_string()]);
+    }
+}
@@ -0,0 +1,52 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::process_collector::{attribute_power, top_processes, ProcessData};
+
+    const MB: u64 = 1024 * 1024;
+
+    fn processes() -> Vec<ProcessData> {
+        vec![
+            ProcessData::new(1, "idle", 0.0, 10 * MB),
+            ProcessData::new(2, "cargo", 30.0, 300 * MB),
+            ProcessData::new(3, "firefox", 5.0, 2048 * MB),
+            ProcessData::new(4, "rustc", 15.0, 900 * MB),
+        ]
+    }
+
+    #[test]
+    fn test_top_by_cpu_and_memory() {
+        let top = top_processes(processes(), 1);
+
+        // Busiest first, then the largest one even though it's not busy
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].name, "cargo");
+        assert_eq!(top[1].name, "firefox");
+        assert_eq!(top[1].memory, 2048.0);
+    }
+
+    #[test]
+    fn test_cpu_share_counts_unlisted_processes() {
+        let top = top_processes(processes(), 1);
+
+        // 30 of the 50% used by all four processes
+        assert!((top[0].cpu_share - 0.6).abs() < 0.001);
+        assert!((top[1].cpu_share - 0.1).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_attribute_package_power() {
+        let mut top = top_processes(processes(), 4);
+        attribute_power(&mut top, Some(40.0));
+        assert_eq!(top[0].power_draw, Some(24.0));
+        assert_eq!(top[1].name, "rustc");
+        assert_eq!(top[1].power_draw, Some(12.0));
+        assert_eq!(top[3].power_draw, Some(0.0));
+
+        // No power sensor
+        attribute_power(&mut top, None);
+        assert!(top.iter().all(|p| p.power_draw.is_none()));
+    }
+}
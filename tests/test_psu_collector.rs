@@ -0,0 +1,54 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::psu_collector::{cpu_share_of_wall, total_wall_draw, PsuData};
+    use crate::collectors::SensorKind::{Fan, Level, Power, Temperature};
+    use crate::collectors::SensorSample;
+
+    #[test]
+    fn test_parse_corsair_psu() {
+        // Recorded from a Corsair HX850i, reports efficiency but no input power
+        let sensors = vec![
+            SensorSample::new("VRM", Temperature, 48.0),
+            SensorSample::new("Case", Temperature, 39.5),
+            SensorSample::new("Fan", Fan, 0.0),
+            SensorSample::new("Total Output", Power, 360.0),
+            SensorSample::new("+12V", Power, 330.0),
+            SensorSample::new("Efficiency", Level, 90.0),
+        ];
+
+        let psu = PsuData::from_sensors("Corsair HX850i", &sensors);
+        assert_eq!(psu.output_power, Some(360.0));
+        assert_eq!(psu.input_power, Some(400.0));
+        assert_eq!(psu.efficiency, Some(90.0));
+        assert_eq!(psu.fan_rpm, Some(0.0));
+        assert_eq!(psu.temp, Some(48.0));
+    }
+
+    #[test]
+    fn test_efficiency_from_input_and_output() {
+        let sensors = vec![
+            SensorSample::new("Input", Power, 250.0),
+            SensorSample::new("Output", Power, 225.0),
+        ];
+
+        let psu = PsuData::from_sensors("NZXT E850", &sensors);
+        assert_eq!(psu.efficiency, Some(90.0));
+        assert_eq!(psu.temp, None);
+    }
+
+    #[test]
+    fn test_cpu_share_of_wall_draw() {
+        let psus = vec![PsuData {
+            input_power: Some(400.0),
+            ..Default::default()
+        }];
+
+        let wall = total_wall_draw(&psus);
+        assert_eq!(wall, Some(400.0));
+        assert_eq!(cpu_share_of_wall(100.0, wall), Some(25.0));
+        assert_eq!(cpu_share_of_wall(100.0, total_wall_draw(&[])), None);
+    }
+}
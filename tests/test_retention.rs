@@ -0,0 +1,233 @@
+#[path = "../src/utils/csv_logger.rs"]
+#[allow(dead_code)]
+mod csv_logger;
+#[path = "../src/utils/retention.rs"]
+#[allow(dead_code)]
+mod retention;
+#[cfg(test)]
+mod tests {
+    use crate::csv_logger::{CsvCpuLogEntry, CsvLogger, CsvSchema, MetricGroup};
+    use crate::retention::{
+        compact_logs, read_rollup, roll_up, write_rollup, Bucket, CompactionReport, Resolution,
+        RetentionPolicy, Stats,
+    };
+    use chrono::{DateTime, FixedOffset, NaiveDate};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn entry(timestamp: &str, temperature: f32, gpu_temperatures: &str) -> CsvCpuLogEntry {
+        CsvCpuLogEntry {
+            timestamp: timestamp.to_string(),
+            temperature_unit: "Celsius".to_string(),
+            temperature: Some(temperature),
+            cpu_usage: 40.0,
+            power_draw: Some(30.0),
+            gpu_temperatures: gpu_temperatures.to_string(),
+            memory_used: 8.5,
+            network_rx_rate: 1.0,
+            network_tx_rate: 0.5,
+            ..Default::default()
+        }
+    }
+
+    fn columns() -> Vec<String> {
+        CsvSchema::new(&[MetricGroup::Gpu]).columns
+    }
+
+    fn bucket(timestamp: &str, temperature: f32, gpu_temperatures: &str) -> Bucket {
+        Bucket::from_entry(&entry(timestamp, temperature, gpu_temperatures), &columns()).unwrap()
+    }
+
+    fn time(value: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(value).unwrap()
+    }
+
+    #[test]
+    fn test_roll_up_to_minutes() {
+        let buckets = vec![
+            bucket("2025-11-18T10:00:10+02:00", 40.0, "50.0,60.0"),
+            bucket("2025-11-18T10:00:40+02:00", 60.0, "54.0,"),
+            bucket("2025-11-18T10:01:05+02:00", 70.0, "56.0,62.0"),
+        ];
+
+        let minutes = roll_up(buckets, Resolution::Minute);
+
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(minutes[0].timestamp, time("2025-11-18T10:00:00+02:00"));
+        assert_eq!(minutes[0].samples, 2);
+        assert_eq!(
+            minutes[0].columns["temperature"],
+            vec![Some(Stats {
+                min: 40.0,
+                max: 60.0,
+                sum: 100.0,
+                count: 2
+            })]
+        );
+        // Per GPU, the second one is missing from one sample
+        let gpus = &minutes[0].columns["gpu_temperatures"];
+        assert_eq!(gpus[0].unwrap().avg(), 52.0);
+        assert_eq!(gpus[1].unwrap().count, 1);
+        assert_eq!(minutes[1].timestamp, time("2025-11-18T10:01:00+02:00"));
+        assert_eq!(minutes[1].samples, 1);
+        // Disabled groups aren't rolled up
+        assert!(!minutes[0].columns.contains_key("memory_used"));
+    }
+
+    #[test]
+    fn test_roll_up_hours_from_minutes() {
+        let samples = (0..90).map(|minute| {
+            let timestamp = format!(
+                "2025-11-18T{:02}:{:02}:30+05:30",
+                10 + minute / 60,
+                minute % 60
+            );
+            bucket(&timestamp, minute as f32, "")
+        });
+
+        let minutes = roll_up(samples, Resolution::Minute);
+        let hours = roll_up(minutes, Resolution::Hour);
+
+        // Aligned to the local hour
+        assert_eq!(hours.len(), 2);
+        assert_eq!(hours[0].timestamp, time("2025-11-18T10:00:00+05:30"));
+        assert_eq!(hours[0].samples, 60);
+        let first = hours[0].columns["temperature"][0].unwrap();
+        assert_eq!((first.min, first.avg(), first.max), (0.0, 29.5, 59.0));
+        let second = hours[1].columns["temperature"][0].unwrap();
+        assert_eq!((second.min, second.avg(), second.max), (60.0, 74.5, 89.0));
+    }
+
+    #[test]
+    fn test_rollup_file_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("rollup.csv");
+        let minutes = roll_up(
+            vec![
+                bucket("2025-11-18T10:00:10+02:00", 40.0, "50.0,"),
+                bucket("2025-11-18T10:00:40+02:00", 60.0, "54.0,"),
+            ],
+            Resolution::Minute,
+        );
+
+        write_rollup(&path, &minutes).unwrap();
+        let read = read_rollup(&path).unwrap();
+
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].timestamp, minutes[0].timestamp);
+        assert_eq!(read[0].samples, 2);
+        assert_eq!(
+            read[0].columns["temperature"],
+            minutes[0].columns["temperature"]
+        );
+        assert_eq!(read[0].columns["gpu_temperatures"][1], None);
+    }
+
+    #[test]
+    fn test_partial_device_keeps_its_count() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("rollup.csv");
+        // Second GPU only reported in the first of three samples
+        let minutes = roll_up(
+            vec![
+                bucket("2025-11-18T10:00:10+02:00", 40.0, "50.0,80.0"),
+                bucket("2025-11-18T10:00:30+02:00", 50.0, "54.0,"),
+                bucket("2025-11-18T10:00:50+02:00", 60.0, "58.0,"),
+            ],
+            Resolution::Minute,
+        );
+        write_rollup(&path, &minutes).unwrap();
+        let read = read_rollup(&path).unwrap();
+        assert_eq!(read[0].columns["gpu_temperatures"][1].unwrap().count, 1);
+
+        // Merged with a later minute, the missing samples don't pull the average down
+        let later = bucket("2025-11-18T10:20:10+02:00", 40.0, "50.0,60.0");
+        let hours = roll_up(read.into_iter().chain([later]), Resolution::Hour);
+        let second_gpu = hours[0].columns["gpu_temperatures"][1].unwrap();
+        assert_eq!(second_gpu.count, 2);
+        assert_eq!(second_gpu.avg(), 70.0);
+    }
+
+    #[test]
+    fn test_compact_logs_by_age() {
+        let dir = TempDir::new().unwrap();
+        let dir_str = dir.path().to_str().unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 11, 30).unwrap();
+
+        // Raw logs for days 1 (recent), 10 (minute) and 40 (hour) days ago
+        for days_ago in [1, 10, 40] {
+            let date = today - chrono::Duration::days(days_ago);
+            let path = dir
+                .path()
+                .join(format!("{}_cpu_logs.csv", date.format("%d-%m-%Y")));
+            let mut logger = CsvLogger::new(Some(dir_str)).unwrap();
+            logger.update_path(path);
+            let timestamp = format!("{}T10:00:00+00:00", date.format("%Y-%m-%d"));
+            logger
+                .write(vec![
+                    entry(&timestamp, 50.0, ""),
+                    entry(&timestamp, 70.0, ""),
+                ])
+                .unwrap();
+            logger.flush_buffer().unwrap();
+        }
+        // Too old to keep at all
+        fs::write(dir.path().join("01-01-2024_cpu_logs_1h.csv"), "").unwrap();
+        // The logger also opened a file for the real today, leave it out
+        let real_today = dir.path().join(format!(
+            "{}_cpu_logs.csv",
+            chrono::Local::now().format("%d-%m-%Y")
+        ));
+        fs::remove_file(real_today).ok();
+
+        let policy = RetentionPolicy {
+            enabled: true,
+            raw_days: 7,
+            minute_days: 30,
+            delete_after_days: 365,
+        };
+        let report = compact_logs(dir.path(), &policy, today).unwrap();
+
+        assert_eq!(
+            report,
+            CompactionReport {
+                rolled_up: 2,
+                deleted: 1,
+                ..Default::default()
+            }
+        );
+        let mut names: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|f| f.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "20-11-2025_cpu_logs_1m.csv",
+                "21-10-2025_cpu_logs_1h.csv",
+                "29-11-2025_cpu_logs.csv",
+            ]
+        );
+        let hours = read_rollup(&dir.path().join("21-10-2025_cpu_logs_1h.csv")).unwrap();
+        assert_eq!(hours[0].samples, 2);
+        assert_eq!(hours[0].columns["temperature"][0].unwrap().avg(), 60.0);
+
+        // Nothing left to do on a second run
+        let report = compact_logs(dir.path(), &policy, today).unwrap();
+        assert_eq!(report, CompactionReport::default());
+    }
+
+    #[test]
+    fn test_rejects_invalid_policy() {
+        let dir = TempDir::new().unwrap();
+        let policy = RetentionPolicy {
+            enabled: true,
+            raw_days: 30,
+            minute_days: 7,
+            delete_after_days: 365,
+        };
+        assert!(!policy.is_valid());
+        assert!(compact_logs(dir.path(), &policy, NaiveDate::MIN).is_err());
+    }
+}
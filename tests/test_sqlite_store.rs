@@ -0,0 +1,299 @@
+#[path = "../src/utils/csv_logger.rs"]
+#[allow(dead_code)]
+mod csv_logger;
+#[path = "../src/utils/sqlite_store.rs"]
+#[allow(dead_code)]
+mod sqlite_store;
+#[cfg(test)]
+mod tests {
+    use crate::csv_logger::CsvCpuLogEntry;
+    use crate::sqlite_store::{Aggregate, DeviceNames, Family, Point, Series, SqliteStore};
+    use chrono::{DateTime, Duration, Local, TimeZone};
+    use rusqlite::Connection;
+    use std::time::Duration as StdDuration;
+    use tempfile::TempDir;
+
+    fn start() -> DateTime<Local> {
+        Local.timestamp_millis_opt(1_763_460_000_000).unwrap() // Minute aligned
+    }
+
+    fn entry(seconds: i64, temperature: f32, gpu_temperatures: &str) -> CsvCpuLogEntry {
+        CsvCpuLogEntry {
+            timestamp: (start() + Duration::seconds(seconds)).to_rfc3339(),
+            temperature_unit: "Celsius".to_string(),
+            temperature: Some(temperature),
+            cpu_usage: 40.0,
+            power_draw: Some(30.0),
+            gpu_temperatures: gpu_temperatures.to_string(),
+            memory_used: 8.5,
+            network_rx_rate: 1.0,
+            network_tx_rate: 0.5,
+            ..Default::default()
+        }
+    }
+
+    fn gpu_names(names: &[&str]) -> DeviceNames {
+        DeviceNames {
+            gpus: names.iter().map(|name| name.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    // One sample every 30 seconds for two minutes, temperatures 40, 50, 60, 70
+    fn filled_store(dir: &TempDir) -> SqliteStore {
+        let mut store = SqliteStore::open(dir.path().join("history.db")).unwrap();
+        let names = gpu_names(&["iGPU", "RTX 4070"]);
+        for (i, temp) in [40.0, 50.0, 60.0, 70.0].into_iter().enumerate() {
+            store
+                .insert(&entry(i as i64 * 30, temp, "55.0,65.0"), &names)
+                .unwrap();
+        }
+        store
+    }
+
+    fn cpu_temp() -> Series {
+        Series::new(Family::Cpu, "temperature")
+    }
+
+    #[test]
+    fn test_range_returns_samples_in_order() {
+        let dir = TempDir::new().unwrap();
+        let store = filled_store(&dir);
+
+        let points = store
+            .range(&cpu_temp(), start(), start() + Duration::seconds(30))
+            .unwrap();
+
+        let start_ms = start().timestamp_millis();
+        assert_eq!(
+            points,
+            vec![
+                Point {
+                    timestamp: start_ms,
+                    value: 40.0
+                },
+                Point {
+                    timestamp: start_ms + 30_000,
+                    value: 50.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_downsample_per_minute() {
+        let dir = TempDir::new().unwrap();
+        let store = filled_store(&dir);
+        let end = start() + Duration::minutes(5);
+        let minute = StdDuration::from_secs(60);
+
+        let avg = store
+            .downsample(&cpu_temp(), start(), end, minute, Aggregate::Avg)
+            .unwrap();
+        let max = store
+            .downsample(&cpu_temp(), start(), end, minute, Aggregate::Max)
+            .unwrap();
+
+        let values = |points: Vec<Point>| points.iter().map(|p| p.value).collect::<Vec<_>>();
+        assert_eq!(avg[1].timestamp - avg[0].timestamp, 60_000);
+        assert_eq!(values(avg), vec![45.0, 65.0]);
+        assert_eq!(values(max), vec![50.0, 70.0]);
+    }
+
+    #[test]
+    fn test_aggregate_per_device() {
+        let dir = TempDir::new().unwrap();
+        let store = filled_store(&dir);
+        let end = start() + Duration::minutes(5);
+
+        let second_gpu = Series::new(Family::Gpu, "temperature").device("RTX 4070");
+        let all_gpus = Series::new(Family::Gpu, "temperature");
+
+        assert_eq!(
+            store
+                .aggregate(&second_gpu, start(), end, Aggregate::Avg)
+                .unwrap(),
+            Some(65.0)
+        );
+        assert_eq!(
+            store
+                .aggregate(&all_gpus, start(), end, Aggregate::Min)
+                .unwrap(),
+            Some(55.0)
+        );
+        // Nothing logged before the start
+        assert_eq!(
+            store
+                .aggregate(
+                    &cpu_temp(),
+                    start() - Duration::hours(1),
+                    start() - Duration::seconds(1),
+                    Aggregate::Max
+                )
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sensor_tables_per_device() {
+        let dir = TempDir::new().unwrap();
+        let mut store = SqliteStore::open(dir.path().join("history.db")).unwrap();
+        store
+            .insert(
+                &CsvCpuLogEntry {
+                    package_temperatures: "61.0,66.5".to_string(),
+                    core_temperatures: "58.0,,60.0".to_string(),
+                    fan_rpms: "1200.0,800.0".to_string(),
+                    rail_voltages: "12.1".to_string(),
+                    dimm_temperatures: "41.5".to_string(),
+                    psu_efficiencies: "90.0".to_string(),
+                    ..entry(0, 60.0, "")
+                },
+                &DeviceNames::default(),
+            )
+            .unwrap();
+        let end = start() + Duration::minutes(1);
+        let value = |series: Series| {
+            store
+                .aggregate(&series, start(), end, Aggregate::Max)
+                .unwrap()
+        };
+
+        // Values without names are stored under their position
+        assert_eq!(
+            value(Series::new(Family::Package, "temperature").device("#1")),
+            Some(66.5)
+        );
+        assert_eq!(
+            value(Series::new(Family::Core, "temperature").device("#1")),
+            None
+        );
+        assert_eq!(
+            value(Series::new(Family::Core, "temperature").device("#2")),
+            Some(60.0)
+        );
+        assert_eq!(
+            value(Series::new(Family::Fan, "rpm").device("#1")),
+            Some(800.0)
+        );
+        assert_eq!(value(Series::new(Family::Voltage, "volts")), Some(12.1));
+        assert_eq!(value(Series::new(Family::Dimm, "temperature")), Some(41.5));
+        assert_eq!(value(Series::new(Family::Psu, "efficiency")), Some(90.0));
+        assert_eq!(value(Series::new(Family::Psu, "output_power")), None);
+    }
+
+    #[test]
+    fn test_rejects_unknown_columns() {
+        let dir = TempDir::new().unwrap();
+        let store = filled_store(&dir);
+        let end = start() + Duration::minutes(5);
+
+        let injected = Series::new(Family::Cpu, "temperature FROM cpu; --");
+        assert!(store.range(&injected, start(), end).is_err());
+        // CPU values aren't per device
+        let device = Series::new(Family::Cpu, "temperature").device("#0");
+        assert!(store.range(&device, start(), end).is_err());
+    }
+
+    #[test]
+    fn test_history_persists_after_reopen() {
+        let dir = TempDir::new().unwrap();
+        drop(filled_store(&dir));
+
+        let mut store = SqliteStore::open(dir.path().join("history.db")).unwrap();
+        store
+            .insert(&entry(120, 80.0, ""), &DeviceNames::default())
+            .unwrap();
+
+        let points = store
+            .range(&cpu_temp(), start(), start() + Duration::minutes(5))
+            .unwrap();
+        assert_eq!(points.len(), 5);
+        // No GPUs in the last sample, no GPU rows either
+        let gpu = store
+            .range(
+                &Series::new(Family::Gpu, "temperature"),
+                start(),
+                start() + Duration::minutes(5),
+            )
+            .unwrap();
+        assert_eq!(gpu.len(), 8);
+    }
+
+    #[test]
+    fn test_device_keeps_history_when_another_disappears() {
+        let dir = TempDir::new().unwrap();
+        let mut store = SqliteStore::open(dir.path().join("history.db")).unwrap();
+        store
+            .insert(
+                &entry(0, 50.0, "45.0,70.0"),
+                &gpu_names(&["iGPU", "RTX 4070"]),
+            )
+            .unwrap();
+        // The iGPU is gone, the dGPU moves to the first position of the column
+        store
+            .insert(&entry(30, 50.0, "72.0"), &gpu_names(&["RTX 4070"]))
+            .unwrap();
+
+        let dgpu = Series::new(Family::Gpu, "temperature").device("RTX 4070");
+        let points = store
+            .range(&dgpu, start(), start() + Duration::minutes(1))
+            .unwrap();
+        let values: Vec<f64> = points.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![70.0, 72.0]);
+    }
+
+    #[test]
+    fn test_identical_devices_stay_apart() {
+        let dir = TempDir::new().unwrap();
+        let mut store = SqliteStore::open(dir.path().join("history.db")).unwrap();
+        store
+            .insert(
+                &entry(0, 50.0, "60.0,65.0"),
+                &gpu_names(&["RTX 4090", "RTX 4090"]),
+            )
+            .unwrap();
+
+        let end = start() + Duration::minutes(1);
+        let value = |device: &str| {
+            let series = Series::new(Family::Gpu, "temperature").device(device);
+            store
+                .aggregate(&series, start(), end, Aggregate::Max)
+                .unwrap()
+        };
+        assert_eq!(value("RTX 4090"), Some(60.0));
+        assert_eq!(value("RTX 4090 #1"), Some(65.0));
+    }
+
+    #[test]
+    fn test_migrates_device_positions() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.db");
+        // Version 2 stored the position in the column
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE gpu (timestamp INTEGER NOT NULL, device INTEGER NOT NULL,
+                 temperature REAL, load REAL, power_draw REAL, vram_used REAL);
+             INSERT INTO gpu VALUES ({}, 1, 66.0, NULL, NULL, NULL);
+             PRAGMA user_version = 2;",
+            start().timestamp_millis()
+        ))
+        .unwrap();
+        drop(conn);
+
+        let store = SqliteStore::open(&path).unwrap();
+        let series = Series::new(Family::Gpu, "temperature").device("#1");
+        assert_eq!(
+            store
+                .aggregate(
+                    &series,
+                    start(),
+                    start() + Duration::minutes(1),
+                    Aggregate::Max
+                )
+                .unwrap(),
+            Some(66.0)
+        );
+    }
+}
@@ -0,0 +1,64 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::storage_collector::StorageData;
+    use crate::collectors::SensorKind::{Data, Level, Load, Temperature};
+    use crate::collectors::SensorSample;
+
+    #[test]
+    fn test_parse_nvme_drive() {
+        // Recorded from a Samsung 980 Pro, wear is reported as used endurance
+        let sensors = vec![
+            SensorSample::new("Temperature 2", Temperature, 52.0),
+            SensorSample::new("Temperature", Temperature, 41.0),
+            SensorSample::new("Used Space", Load, 63.5),
+            SensorSample::new("Percentage Used", Level, 4.0),
+            SensorSample::new("Available Spare", Level, 100.0),
+            SensorSample::new("Data Written", Data, 18250.0),
+        ];
+
+        let drive = StorageData::from_sensors("Samsung SSD 980 PRO 1TB", &sensors);
+        assert_eq!(drive.name, "Samsung SSD 980 PRO 1TB");
+        assert_eq!(drive.temp, Some(41.0));
+        assert_eq!(drive.used_space, Some(63.5));
+        assert_eq!(drive.remaining_life, Some(96.0));
+        assert_eq!(drive.available_spare, Some(100.0));
+        assert_eq!(drive.data_written, Some(18250.0));
+    }
+
+    #[test]
+    fn test_parse_sata_ssd() {
+        let sensors = vec![
+            SensorSample::new("Airflow Temperature", Temperature, 35.0),
+            SensorSample::new("Used Space", Load, 12.0),
+            SensorSample::new("Remaining Life", Level, 88.0),
+            SensorSample::new("Total Bytes Written", Data, 5120.0),
+        ];
+
+        // No composite sensor, the first temperature is used
+        let drive = StorageData::from_sensors("Crucial MX500", &sensors);
+        assert_eq!(drive.temp, Some(35.0));
+        assert_eq!(drive.used_space, Some(12.0));
+        assert_eq!(drive.remaining_life, Some(88.0));
+        assert_eq!(drive.available_spare, None);
+        assert_eq!(drive.data_written, Some(5120.0));
+    }
+
+    #[test]
+    fn test_worn_out_drive_and_missing_sensors() {
+        // Used endurance can go past 100%
+        let drive = StorageData::from_sensors(
+            "Old NVMe",
+            &[SensorSample::new("Percentage Used", Level, 120.0)],
+        );
+        assert_eq!(drive.remaining_life, Some(0.0));
+
+        // Hard drives without SMART data report nothing
+        let drive = StorageData::from_sensors("USB HDD", &[]);
+        assert_eq!(drive.temp, None);
+        assert_eq!(drive.used_space, None);
+        assert_eq!(drive.remaining_life, None);
+    }
+}
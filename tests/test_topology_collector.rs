@@ -0,0 +1,107 @@
+#[path = "../src/collectors/mod.rs"]
+#[allow(dead_code)]
+mod collectors;
+#[cfg(test)]
+mod tests {
+    use crate::collectors::topology_collector::{CoreType, CpuTopology, LogicalCpuInfo};
+    use crate::collectors::CoreStats;
+
+    fn cpu(index: usize, core_id: u64, cluster_id: u64, efficiency_class: u8) -> LogicalCpuInfo {
+        LogicalCpuInfo {
+            index,
+            core_id,
+            cluster_id,
+            efficiency_class,
+        }
+    }
+
+    fn values(values: &[f32]) -> Vec<CoreStats> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| CoreStats {
+                name: format!("CPU {}", i),
+                value: *value,
+            })
+            .collect()
+    }
+
+    // 2 hyperthreaded P-cores and a 4 core E-core module, like a small Alder Lake
+    fn hybrid() -> CpuTopology {
+        CpuTopology::from_logical(vec![
+            cpu(0, 0, 0, 1),
+            cpu(1, 0, 0, 1),
+            cpu(2, 1, 0, 1),
+            cpu(3, 1, 0, 1),
+            cpu(4, 2, 1, 0),
+            cpu(5, 3, 1, 0),
+            cpu(6, 4, 1, 0),
+            cpu(7, 5, 1, 0),
+        ])
+    }
+
+    #[test]
+    fn test_hybrid_core_types_and_siblings() {
+        let topology = hybrid();
+        assert!(topology.is_hybrid());
+        assert_eq!(topology.get(0).unwrap().core_type, CoreType::Performance);
+        assert!(!topology.get(2).unwrap().smt_sibling);
+        assert!(topology.get(3).unwrap().smt_sibling);
+        assert_eq!(topology.get(3).unwrap().physical_core, 1);
+        assert_eq!(topology.get(6).unwrap().core_type, CoreType::Efficiency);
+        assert_eq!(topology.get(6).unwrap().physical_core, 4);
+    }
+
+    #[test]
+    fn test_groups_per_logical_and_physical_lists() {
+        let topology = hybrid();
+
+        // Usage: one entry per logical processor
+        let groups = topology.groups(8);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].label, "P-cores (cluster 0)");
+        assert_eq!(groups[0].members, vec![0, 1, 2, 3]);
+        assert_eq!(groups[1].label, "E-cores (cluster 1)");
+
+        // LHM temperatures: one entry per physical core
+        let groups = topology.groups(6);
+        assert_eq!(groups[0].members, vec![0, 1]);
+        assert_eq!(groups[1].members, vec![2, 3, 4, 5]);
+
+        // Per-CCD sensors don't line up, everything in one group
+        let groups = topology.groups(3);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].label, "Cores");
+        assert_eq!(groups[0].members, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_aggregate_per_group() {
+        let topology = hybrid();
+        let stats = topology.aggregate(&values(&[4.8, 4.6, 5.0, 4.4, 3.2, 3.6, 3.4, 3.0]));
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].label, "P-cores (cluster 0)");
+        assert!((stats[0].average - 4.7).abs() < 0.001);
+        assert_eq!(stats[0].max, 5.0);
+        assert!((stats[1].average - 3.3).abs() < 0.001);
+        assert_eq!(stats[1].max, 3.6);
+    }
+
+    #[test]
+    fn test_dual_ccd_is_not_hybrid() {
+        // 2 CCDs with 2 cores each, SMT off
+        let topology = CpuTopology::from_logical(vec![
+            cpu(0, 0, 0, 0),
+            cpu(1, 1, 0, 0),
+            cpu(2, 8, 1, 0),
+            cpu(3, 9, 1, 0),
+        ]);
+        assert!(!topology.is_hybrid());
+
+        let groups = topology.groups(4);
+        assert_eq!(groups[0].label, "Cores (cluster 0)");
+        assert_eq!(groups[1].label, "Cores (cluster 1)");
+        assert_eq!(groups[1].members, vec![2, 3]);
+    }
+}
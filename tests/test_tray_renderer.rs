@@ -0,0 +1,59 @@
+#[path = "../src/app/tray_renderer.rs"]
+#[allow(dead_code)]
+mod tray_renderer;
+#[cfg(test)]
+mod tests {
+    use crate::tray_renderer::{render, TempLevel, TrayIconStyle, ICON_SIZE};
+    use image::{Rgba, RgbaImage};
+
+    fn count_pixels(image: &RgbaImage, color: Rgba<u8>) -> usize {
+        image.pixels().filter(|pixel| **pixel == color).count()
+    }
+
+    #[test]
+    fn test_levels_follow_thresholds() {
+        assert_eq!(TempLevel::new(40.0, 40.0, 70.0), TempLevel::Low);
+        assert_eq!(TempLevel::new(55.0, 40.0, 70.0), TempLevel::Medium);
+        assert_eq!(TempLevel::new(70.0, 40.0, 70.0), TempLevel::High);
+    }
+
+    #[test]
+    fn test_digits_colored_by_level() {
+        let cool = render(TrayIconStyle::Digits, Some(35.0), 40.0, 70.0).unwrap();
+        let hot = render(TrayIconStyle::Digits, Some(88.0), 40.0, 70.0).unwrap();
+
+        assert_eq!(cool.dimensions(), (ICON_SIZE, ICON_SIZE));
+        assert!(count_pixels(&cool, TempLevel::Low.color()) > 0);
+        assert_eq!(count_pixels(&cool, TempLevel::High.color()), 0);
+        assert!(count_pixels(&hot, TempLevel::High.color()) > 0);
+        // "88" lights up more pixels than "35"
+        assert!(
+            count_pixels(&hot, TempLevel::High.color())
+                > count_pixels(&cool, TempLevel::Low.color())
+        );
+    }
+
+    #[test]
+    fn test_gauge_fills_towards_high_threshold() {
+        let color = TempLevel::Medium.color();
+        let half = render(TrayIconStyle::Gauge, Some(40.0), 30.0, 80.0).unwrap();
+        let full = render(TrayIconStyle::Gauge, Some(79.0), 30.0, 80.0).unwrap();
+        let over = render(TrayIconStyle::Gauge, Some(95.0), 30.0, 80.0).unwrap();
+
+        let half_fill = count_pixels(&half, color);
+        assert!(half_fill > 0);
+        assert!(count_pixels(&full, color) > half_fill);
+        // Past the high threshold the gauge is full and red
+        assert_eq!(count_pixels(&over, color), 0);
+        assert!(count_pixels(&over, TempLevel::High.color()) >= count_pixels(&full, color));
+    }
+
+    #[test]
+    fn test_unavailable_and_logo() {
+        let unavailable = render(TrayIconStyle::Gauge, None, 40.0, 70.0).unwrap();
+        for level in [TempLevel::Low, TempLevel::Medium, TempLevel::High] {
+            assert_eq!(count_pixels(&unavailable, level.color()), 0);
+        }
+        assert!(render(TrayIconStyle::Logo, Some(50.0), 40.0, 70.0).is_none());
+    }
+}